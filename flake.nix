@@ -41,6 +41,12 @@
               rustup component add rustfmt
             fi
 
+            # Set up the bare-metal target used by `cargo build-embedded`
+            if ! rustup target list --installed | grep -q "thumbv7em-none-eabihf"; then
+              echo "🔧 Installing thumbv7em-none-eabihf target...";
+              rustup target add thumbv7em-none-eabihf
+            fi
+
             # Set up cargo-tarpaulin for code coverage
             if ! cargo install --list | grep -q "cargo-tarpaulin"; then
               echo "🔧 Installing cargo-tarpaulin...";
@@ -0,0 +1,29 @@
+//! Structure-aware fuzzing of CONNECT decoding: an arbitrary CONNECT (with nested Will,
+//! properties and credentials) is encoded, checked to round-trip, then mutated and decoded.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use midge::data_representation::{MqttDecode, MqttEncode};
+use midge::packet::{Connect, Packet};
+
+// properties held per block, in the CONNECT and in its Will
+const N: usize = 4;
+
+fuzz_target!(|input: (Connect<'_, N>, Vec<(u16, u8)>)| {
+    let (connect, mutations) = input;
+
+    // unconstrained fields (e.g. a wildcard Will Topic) may not encode; nothing to decode then
+    let mut buffer = vec![0; connect.encoded_len()];
+    let Ok(len) = connect.encode(&mut buffer) else {
+        return;
+    };
+    assert_eq!(Connect::<N>::decode(&buffer[..len]), Ok((connect, len)));
+
+    // flip bits across the encoding, where the flags, lengths and properties live
+    for (offset, mask) in mutations {
+        buffer[usize::from(offset) % len] ^= mask;
+    }
+    let _ = Connect::<N>::decode(&buffer[..len]);
+    let _ = Packet::<N>::decode(&buffer[..len]);
+});
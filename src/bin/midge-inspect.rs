@@ -0,0 +1,157 @@
+//! Packet inspector: decodes captured MQTT bytes and prints each packet on one line.
+//!
+//! ```text
+//! midge-inspect [--lenient] <hex>
+//! midge-inspect [--lenient] --base64 <base64>
+//! midge-inspect [--lenient] --file <path>
+//! ```
+//!
+//! Hex may be separated by whitespace or colons and prefixed with `0x`. Back-to-back
+//! packets are decoded in turn; `--lenient` accepts strings with control characters.
+
+use midge::data_representation::Utf8Validation;
+use midge::packet::Packet;
+use std::process::ExitCode;
+
+// the most properties, topic filters or reason codes shown for a single packet
+const MAX_ENTRIES: usize = 32;
+
+const USAGE: &str = "usage: midge-inspect [--lenient] (<hex> | --base64 <base64> | --file <path>)";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match read_input(&args).and_then(|(bytes, validation)| inspect(&bytes, validation)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// returns the bytes named by the arguments, and how strictly to validate their strings
+fn read_input(args: &[String]) -> Result<(Vec<u8>, Utf8Validation), String> {
+    let (validation, args) = match args {
+        [flag, rest @ ..] if flag == "--lenient" => (Utf8Validation::Lenient, rest),
+        _ => (Utf8Validation::Strict, args),
+    };
+
+    let bytes = match args {
+        [flag, path] if flag == "--file" => {
+            std::fs::read(path).map_err(|error| format!("cannot read {path}: {error}"))?
+        }
+        [flag, encoded] if flag == "--base64" => parse_base64(encoded)?,
+        [hex] if !hex.starts_with("--") => parse_hex(hex)?,
+        _ => return Err(USAGE.to_string()),
+    };
+
+    Ok((bytes, validation))
+}
+
+// prints every packet in the buffer, stopping at the first that fails to decode
+fn inspect(bytes: &[u8], validation: Utf8Validation) -> Result<(), String> {
+    let mut offset = 0;
+
+    while let Some(rest) = bytes.get(offset..).filter(|rest| !rest.is_empty()) {
+        let (packet, len) = Packet::<MAX_ENTRIES>::decode_with_validation(rest, validation)
+            .map_err(|error| format!("offset {offset}: {error:?}"))?;
+
+        println!("{offset:>6}  {packet}");
+        offset += len;
+    }
+
+    Ok(())
+}
+
+fn parse_hex(input: &str) -> Result<Vec<u8>, String> {
+    let digits: Vec<u8> = input
+        .split(|c: char| c.is_whitespace() || c == ':')
+        .map(|group| group.strip_prefix("0x").unwrap_or(group))
+        .flat_map(str::bytes)
+        .collect();
+
+    if !digits.len().is_multiple_of(2) {
+        return Err("hex input has an odd number of digits".to_string());
+    }
+
+    digits
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| format!("invalid hex byte {:?}", String::from_utf8_lossy(pair)))
+        })
+        .collect()
+}
+
+fn parse_base64(input: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    let mut bits: u32 = 0;
+    let mut bit_count = 0;
+
+    for c in input
+        .bytes()
+        .filter(|c| !c.is_ascii_whitespace() && *c != b'=')
+    {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return Err(format!("invalid base64 character {:?}", char::from(c))),
+        };
+
+        // every 4 characters carry 3 bytes; emit each byte once 8 bits have accumulated
+        bits = (bits << 6) | u32::from(value);
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            bytes.push((bits >> bit_count) as u8);
+        }
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod test_inspect {
+    use super::*;
+
+    #[test]
+    fn parses_hex_with_separators() {
+        assert_eq!(parse_hex("40 02 00 01"), Ok(vec![0x40, 0x02, 0x00, 0x01]));
+        assert_eq!(parse_hex("c0:00"), Ok(vec![0xC0, 0x00]));
+        assert_eq!(parse_hex("0xd000"), Ok(vec![0xD0, 0x00]));
+        assert!(parse_hex("c00").is_err());
+        assert!(parse_hex("zz").is_err());
+    }
+
+    #[test]
+    fn parses_base64() {
+        assert_eq!(parse_base64("QAIAAQ=="), Ok(vec![0x40, 0x02, 0x00, 0x01]));
+        assert_eq!(parse_base64("wAA="), Ok(vec![0xC0, 0x00]));
+        assert!(parse_base64("wA*").is_err());
+    }
+
+    #[test]
+    fn reads_arguments() {
+        let args = ["--lenient".to_string(), "c000".to_string()];
+        assert_eq!(
+            read_input(&args),
+            Ok((vec![0xC0, 0x00], Utf8Validation::Lenient))
+        );
+        assert_eq!(read_input(&[]), Err(USAGE.to_string()));
+    }
+
+    #[test]
+    fn inspects_back_to_back_packets() {
+        assert_eq!(
+            inspect(&[0xC0, 0x00, 0xD0, 0x00], Utf8Validation::Strict),
+            Ok(())
+        );
+        assert!(inspect(&[0xC0, 0x00, 0x40], Utf8Validation::Strict).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+use super::{BinaryDataRef, TwoByteInt};
+use super::{DataRepresentationError, MqttDecode, MqttEncode};
+
+/// Fixed-capacity MQTT binary data holding up to N bytes.
+///
+/// The 2-byte length prefix caps the data at 65,535 bytes, so larger capacities are
+/// rejected at compile time:
+///
+/// ```compile_fail
+/// let too_large = midge::data_representation::BinaryData::<65536>::new();
+/// ```
+#[derive(Debug, Clone)]
+pub struct BinaryData<const N: usize> {
+    buffer: [u8; N],
+    length: u16,
+}
+
+const MAX_DATA_LEN: usize = 65535;
+
+impl<const N: usize> BinaryData<N> {
+    /// Creates empty binary data
+    pub const fn new() -> Self {
+        const {
+            assert!(
+                N <= MAX_DATA_LEN,
+                "BinaryData capacity exceeds 65,535 bytes"
+            )
+        };
+
+        Self {
+            buffer: [0; N],
+            length: 0,
+        }
+    }
+
+    /// Sets the value of the binary data, enforcing the length limits of the spec
+    pub fn set(&mut self, value: &[u8]) -> Result<(), DataRepresentationError> {
+        // limit the data length to the capacity and the maximum permitted by the spec
+        if value.len() > MAX_DATA_LEN {
+            return Err(DataRepresentationError::BinaryDataTooLong);
+        }
+
+        let length =
+            u16::try_from(value.len()).map_err(|_| DataRepresentationError::BinaryDataTooLong)?;
+
+        self.buffer
+            .get_mut(..value.len())
+            .ok_or(DataRepresentationError::BinaryDataTooLong)?
+            .copy_from_slice(value);
+        self.length = length;
+
+        Ok(())
+    }
+
+    /// Returns the currently stored bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        self.buffer.get(..self.length as usize).unwrap_or_default()
+    }
+}
+
+impl<const N: usize> MqttEncode for BinaryData<N> {
+    type Error = DataRepresentationError;
+
+    /// Encodes the binary data into the MQTT-spec format
+    /// Returns the length (including the 2 bytes of length data) of the encoded data
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        let data = self.as_bytes();
+        let encoded_len = 2 + data.len();
+
+        // Ensure the buffer is large enough for the 2 length bytes plus the data;
+        // an oversized buffer is fine, only the necessary bytes are written
+        let (length_bytes, data_target) = buffer
+            .get_mut(..encoded_len)
+            .ok_or(DataRepresentationError::BinaryBufferOverflow)?
+            .split_at_mut(2);
+
+        // Encode length as two-byte integer (in bytes)
+        length_bytes.copy_from_slice(&TwoByteInt::from(self.length).to_bytes());
+
+        // Copy the data into the buffer
+        data_target.copy_from_slice(data);
+
+        Ok(encoded_len)
+    }
+
+    fn encoded_len(&self) -> usize {
+        2 + self.length as usize
+    }
+}
+
+impl<const N: usize> MqttDecode<'_> for BinaryData<N> {
+    type Error = DataRepresentationError;
+
+    /// Decodes MQTT binary data from a byte buffer
+    fn decode(buffer: &[u8]) -> Result<(Self, usize), Self::Error> {
+        // length checks are shared with the borrowed representation
+        let (data, consumed) = BinaryDataRef::decode(buffer)?;
+
+        let mut binary_data = BinaryData::new();
+        binary_data.set(data.as_bytes())?;
+
+        Ok((binary_data, consumed))
+    }
+}
+
+// only the stored bytes are compared; a shorter value leaves stale bytes past its length
+impl<const N: usize> PartialEq for BinaryData<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl<const N: usize> Eq for BinaryData<N> {}
+
+impl<const N: usize> Default for BinaryData<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test_binary_data {
+    use super::*;
+
+    #[test]
+    fn encodes_simple_data() {
+        let mut binary_data = BinaryData::<4>::new();
+        binary_data.set(&[0xDE, 0xAD]).unwrap();
+
+        let mut buffer = [0; 4];
+
+        let expected_buffer = [
+            0x00, 0x02, // length of 2 bytes
+            0xDE, 0xAD, // data
+        ];
+
+        let encoded_len = binary_data.encode(&mut buffer).unwrap();
+
+        assert_eq!(encoded_len, 4);
+        assert_eq!(buffer, expected_buffer);
+    }
+
+    #[test]
+    fn decodes_simple_data() {
+        let buffer = [
+            0x00, 0x03, // length of 3 bytes
+            0x01, 0x02, 0x03, // data
+        ];
+
+        let (binary_data, consumed) = BinaryData::<3>::decode(&buffer).unwrap();
+
+        assert_eq!(binary_data.as_bytes(), &[0x01, 0x02, 0x03]);
+        assert_eq!(consumed, 5);
+    }
+
+    #[test]
+    fn encodes_empty_data() {
+        let binary_data = BinaryData::<4>::new();
+        let mut buffer = [0xFF; 2];
+
+        let encoded_len = binary_data.encode(&mut buffer).unwrap();
+
+        assert_eq!(encoded_len, 2);
+        assert_eq!(buffer, [0x00, 0x00]);
+    }
+
+    #[test]
+    fn rejects_data_over_capacity() {
+        let mut binary_data = BinaryData::<2>::new();
+
+        assert_eq!(
+            binary_data.set(&[0x01, 0x02, 0x03]),
+            Err(DataRepresentationError::BinaryDataTooLong)
+        );
+    }
+
+    #[test]
+    fn rejects_undersized_encode_buffer() {
+        let mut binary_data = BinaryData::<4>::new();
+        binary_data.set(&[0x01, 0x02]).unwrap();
+
+        let mut buffer = [0; 3];
+
+        assert_eq!(
+            binary_data.encode(&mut buffer),
+            Err(DataRepresentationError::BinaryBufferOverflow)
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_buffer() {
+        let buffer = [
+            0x00, 0x04, // length of 4 bytes
+            0x01, 0x02, // only 2 bytes of data
+        ];
+
+        assert_eq!(
+            BinaryData::<4>::decode(&buffer).map(|(data, _)| data),
+            Err(DataRepresentationError::BinaryMalformedBuffer)
+        );
+    }
+
+    #[test]
+    fn test_reversibility() {
+        let mut original = BinaryData::<8>::new();
+        original.set(&[0x00, 0x10, 0x20, 0x30, 0x40]).unwrap();
+
+        let mut buffer = [0; 10];
+        original.encode(&mut buffer).unwrap();
+        let (decoded, _) = BinaryData::<8>::decode(&buffer).unwrap();
+
+        assert_eq!(original, decoded);
+    }
+
+    #[test]
+    fn equality_ignores_bytes_past_length() {
+        let mut overwritten = BinaryData::<4>::new();
+        overwritten.set(&[0x01, 0x02, 0x03]).unwrap();
+        overwritten.set(&[0x01]).unwrap();
+
+        let mut fresh = BinaryData::<4>::new();
+        fresh.set(&[0x01]).unwrap();
+
+        assert_eq!(overwritten, fresh);
+    }
+}
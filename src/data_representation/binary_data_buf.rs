@@ -0,0 +1,122 @@
+use super::{BinaryDataRef, DataRepresentationError, MqttDecode, MqttEncode};
+use alloc::vec::Vec;
+
+/// Heap-backed MQTT binary data, for hosted environments where a fixed capacity
+/// is awkward. Enforces the same length limits as `BinaryData`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BinaryDataBuf {
+    value: Vec<u8>,
+}
+
+impl BinaryDataBuf {
+    /// Creates empty binary data
+    pub const fn new() -> Self {
+        Self { value: Vec::new() }
+    }
+
+    /// Sets the value of the binary data, enforcing the length limits of the spec
+    pub fn set(&mut self, value: &[u8]) -> Result<(), DataRepresentationError> {
+        BinaryDataRef::new(value)?;
+
+        self.value.clear();
+        self.value.extend_from_slice(value);
+
+        Ok(())
+    }
+
+    /// Returns the currently stored bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.value
+    }
+
+    /// Returns the length of the data in bytes, excluding the 2 bytes of length data
+    pub fn len(&self) -> usize {
+        self.value.len()
+    }
+
+    /// Returns whether the data is empty
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    /// Unwraps the underlying `Vec`
+    pub fn into_vec(self) -> Vec<u8> {
+        self.value
+    }
+}
+
+impl TryFrom<&[u8]> for BinaryDataBuf {
+    type Error = DataRepresentationError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let mut binary_data = Self::new();
+        binary_data.set(value)?;
+
+        Ok(binary_data)
+    }
+}
+
+/// Takes ownership of the `Vec` without copying, once it passes validation
+impl TryFrom<Vec<u8>> for BinaryDataBuf {
+    type Error = DataRepresentationError;
+
+    fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+        BinaryDataRef::new(&value)?;
+
+        Ok(Self { value })
+    }
+}
+
+impl MqttEncode for BinaryDataBuf {
+    type Error = DataRepresentationError;
+
+    /// Encodes the binary data into the MQTT-spec format
+    /// Returns the length (including the 2 bytes of length data) of the encoded data
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        BinaryDataRef::new(&self.value)?.encode(buffer)
+    }
+
+    fn encoded_len(&self) -> usize {
+        2 + self.len()
+    }
+}
+
+impl MqttDecode<'_> for BinaryDataBuf {
+    type Error = DataRepresentationError;
+
+    /// Decodes MQTT binary data from a byte buffer
+    fn decode(buffer: &[u8]) -> Result<(Self, usize), Self::Error> {
+        let (data, consumed) = BinaryDataRef::decode(buffer)?;
+
+        let value = Vec::from(data.as_bytes());
+
+        Ok((Self { value }, consumed))
+    }
+}
+
+#[cfg(test)]
+mod test_binary_data_buf {
+    use super::*;
+
+    #[test]
+    fn test_reversibility() {
+        let original = BinaryDataBuf::try_from(alloc::vec![0xDE, 0xAD, 0xBE, 0xEF]).unwrap();
+
+        let mut buffer = [0; 8];
+        let encoded_len = original.encode(&mut buffer).unwrap();
+        let (decoded, consumed) = BinaryDataBuf::decode(&buffer).unwrap();
+
+        assert_eq!(encoded_len, 6);
+        assert_eq!(original.encoded_len(), 6);
+        assert_eq!(consumed, 6);
+        assert_eq!(original, decoded);
+    }
+
+    #[test]
+    fn rejects_data_over_spec_limit() {
+        assert_eq!(
+            BinaryDataBuf::try_from(alloc::vec![0; 65536]),
+            Err(DataRepresentationError::BinaryDataTooLong)
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use super::TwoByteInt;
+use super::{DataRepresentationError, MqttDecode, MqttEncode};
+
+/// Borrowed MQTT binary data, pointing into the buffer it was decoded from.
+/// Unlike `BinaryData`, decoding copies nothing and requires no capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BinaryDataRef<'a> {
+    value: &'a [u8],
+    length: u16,
+}
+
+const MAX_DATA_LEN: usize = 65535;
+
+impl<'a> BinaryDataRef<'a> {
+    /// Wraps a byte slice, enforcing the length limits of the spec
+    pub fn new(value: &'a [u8]) -> Result<Self, DataRepresentationError> {
+        if value.len() > MAX_DATA_LEN {
+            return Err(DataRepresentationError::BinaryDataTooLong);
+        }
+
+        let length =
+            u16::try_from(value.len()).map_err(|_| DataRepresentationError::BinaryDataTooLong)?;
+
+        Ok(Self { value, length })
+    }
+
+    /// Returns the borrowed bytes
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.value
+    }
+
+    /// Returns the length of the data in bytes, excluding the 2 bytes of length data
+    pub fn len(&self) -> usize {
+        self.length as usize
+    }
+
+    /// Returns whether the data is empty
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+}
+
+impl MqttEncode for BinaryDataRef<'_> {
+    type Error = DataRepresentationError;
+
+    /// Encodes the binary data into the MQTT-spec format
+    /// Returns the length (including the 2 bytes of length data) of the encoded data
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        let encoded_len = 2 + self.value.len();
+
+        let (length_bytes, data_target) = buffer
+            .get_mut(..encoded_len)
+            .ok_or(DataRepresentationError::BinaryBufferOverflow)?
+            .split_at_mut(2);
+
+        // Encode length as two-byte integer (in bytes)
+        length_bytes.copy_from_slice(&TwoByteInt::from(self.length).to_bytes());
+
+        // Copy the data into the buffer
+        data_target.copy_from_slice(self.value);
+
+        Ok(encoded_len)
+    }
+
+    /// Returns the length of the encoded data, including the 2 bytes of length data
+    fn encoded_len(&self) -> usize {
+        2 + self.len()
+    }
+}
+
+impl<'a> MqttDecode<'a> for BinaryDataRef<'a> {
+    type Error = DataRepresentationError;
+
+    /// Decodes MQTT binary data from a byte buffer, borrowing the data from it
+    fn decode(buffer: &'a [u8]) -> Result<(Self, usize), Self::Error> {
+        // Read the length
+        let (length_bytes, rest) = buffer
+            .split_first_chunk::<2>()
+            .ok_or(DataRepresentationError::BinaryMalformedBuffer)?;
+        let length = TwoByteInt::from_bytes(*length_bytes).value();
+
+        // Ensure the buffer is large enough to hold the supposed number of bytes
+        let value = rest
+            .get(..length as usize)
+            .ok_or(DataRepresentationError::BinaryMalformedBuffer)?;
+
+        Ok((Self { value, length }, 2 + length as usize))
+    }
+}
+
+#[cfg(test)]
+mod test_binary_data_ref {
+    use super::*;
+
+    #[test]
+    fn decodes_simple_data() {
+        let buffer = [
+            0x00, 0x03, // length of 3 bytes
+            0x01, 0x02, 0x03, // data
+            0xFF, // trailing byte belonging to the next field
+        ];
+
+        let (binary_data, consumed) = BinaryDataRef::decode(&buffer).unwrap();
+
+        assert_eq!(binary_data.as_bytes(), &[0x01, 0x02, 0x03]);
+        assert_eq!(consumed, 5);
+    }
+
+    #[test]
+    fn borrows_from_the_buffer() {
+        let buffer = [0x00, 0x02, 0xDE, 0xAD];
+
+        let (binary_data, _) = BinaryDataRef::decode(&buffer).unwrap();
+
+        assert_eq!(binary_data.as_bytes().as_ptr(), buffer[2..].as_ptr());
+    }
+
+    #[test]
+    fn encodes_simple_data() {
+        let binary_data = BinaryDataRef::new(&[0xDE, 0xAD]).unwrap();
+        let mut buffer = [0; 4];
+
+        let encoded_len = binary_data.encode(&mut buffer).unwrap();
+
+        assert_eq!(encoded_len, 4);
+        assert_eq!(buffer, [0x00, 0x02, 0xDE, 0xAD]);
+    }
+
+    #[test]
+    fn rejects_undersized_encode_buffer() {
+        let binary_data = BinaryDataRef::new(&[0xDE, 0xAD]).unwrap();
+        let mut buffer = [0; 3];
+
+        assert_eq!(
+            binary_data.encode(&mut buffer),
+            Err(DataRepresentationError::BinaryBufferOverflow)
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_buffer() {
+        let buffer = [0x00, 0x04, 0x01, 0x02];
+
+        assert_eq!(
+            BinaryDataRef::decode(&buffer).map(|(data, _)| data),
+            Err(DataRepresentationError::BinaryMalformedBuffer)
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use super::{DataRepresentationError, FourByteInt, MqttDecode, TwoByteInt};
+use super::{Utf8Validation, VariableByteInt};
+
+/// A read cursor over a received buffer. Each `read_*` either reads the whole value and
+/// advances, or leaves the cursor untouched and returns an error. Strings and byte slices
+/// are borrowed from the buffer, and validated strictly unless the reader is created
+/// `with_validation`.
+#[derive(Debug, Clone)]
+pub struct ByteReader<'a> {
+    buffer: &'a [u8],
+    position: usize,
+    validation: Utf8Validation,
+}
+
+impl<'a> ByteReader<'a> {
+    /// Creates a reader positioned at the start of the buffer
+    pub fn new(buffer: &'a [u8]) -> Self {
+        Self::with_validation(buffer, Utf8Validation::Strict)
+    }
+
+    /// Creates a reader positioned at the start of the buffer, validating strings as given
+    pub fn with_validation(buffer: &'a [u8], validation: Utf8Validation) -> Self {
+        Self {
+            buffer,
+            position: 0,
+            validation,
+        }
+    }
+
+    /// Returns how strictly strings are validated
+    pub fn validation(&self) -> Utf8Validation {
+        self.validation
+    }
+
+    /// Returns the number of bytes consumed so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the number of bytes not yet consumed
+    pub fn remaining(&self) -> usize {
+        self.buffer.len().saturating_sub(self.position)
+    }
+
+    /// Returns whether every byte has been consumed
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Reads a single byte
+    pub fn read_u8(&mut self) -> Result<u8, DataRepresentationError> {
+        let [byte] = self.read_array::<1>()?;
+        Ok(byte)
+    }
+
+    /// Reads a Two Byte Integer (big-endian)
+    pub fn read_u16(&mut self) -> Result<u16, DataRepresentationError> {
+        Ok(TwoByteInt::from_bytes(self.read_array()?).value())
+    }
+
+    /// Reads a Four Byte Integer (big-endian)
+    pub fn read_u32(&mut self) -> Result<u32, DataRepresentationError> {
+        Ok(FourByteInt::from_bytes(self.read_array()?).value())
+    }
+
+    /// Reads a Variable Byte Integer
+    pub fn read_vbi(&mut self) -> Result<VariableByteInt, DataRepresentationError> {
+        let (value, consumed) = VariableByteInt::decode(self.rest())?;
+        self.position += consumed;
+
+        Ok(value)
+    }
+
+    /// Reads any decodable value, such as a property block, from its wire format
+    pub fn read_decoded<T: MqttDecode<'a>>(&mut self) -> Result<T, T::Error> {
+        let (value, consumed) = T::decode(self.rest())?;
+        self.position += consumed;
+
+        Ok(value)
+    }
+
+    /// Reads a UTF-8 string: 2 bytes of length data followed by the string itself
+    pub fn read_utf8_str(&mut self) -> Result<&'a str, DataRepresentationError> {
+        let start = self.position;
+
+        let result = self
+            .read_u16()
+            .and_then(|length| self.read_bytes(length as usize))
+            .and_then(|bytes| self.validation.parse(bytes));
+
+        // a partially read string must not move the cursor
+        if result.is_err() {
+            self.position = start;
+        }
+
+        result
+    }
+
+    /// Reads the next `n` bytes, with no length prefix
+    pub fn read_bytes(&mut self, n: usize) -> Result<&'a [u8], DataRepresentationError> {
+        let bytes = self
+            .rest()
+            .get(..n)
+            .ok_or(DataRepresentationError::BufferUnderflow)?;
+        self.position += n;
+
+        Ok(bytes)
+    }
+
+    /// Returns every byte not yet consumed, without advancing
+    pub fn rest(&self) -> &'a [u8] {
+        self.buffer.get(self.position..).unwrap_or_default()
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], DataRepresentationError> {
+        let (bytes, _) = self
+            .rest()
+            .split_first_chunk::<N>()
+            .ok_or(DataRepresentationError::BufferUnderflow)?;
+        self.position += N;
+
+        Ok(*bytes)
+    }
+}
+
+#[cfg(test)]
+mod test_byte_reader {
+    use super::*;
+
+    #[test]
+    fn reads_sequentially() {
+        let buffer = [0x10, 0x12, 0x34, 0xC1, 0x02, 0x00, 0x02, 0x41, 0x42, 0xFF];
+        let mut reader = ByteReader::new(&buffer);
+
+        assert_eq!(reader.read_u8(), Ok(0x10));
+        assert_eq!(reader.read_u16(), Ok(0x1234));
+        assert_eq!(reader.read_vbi().map(VariableByteInt::value), Ok(321));
+        assert_eq!(reader.read_utf8_str(), Ok("AB"));
+
+        assert_eq!(reader.position(), 9);
+        assert_eq!(reader.remaining(), 1);
+        assert_eq!(reader.rest(), &[0xFF]);
+    }
+
+    #[test]
+    fn reads_decodable_values() {
+        use crate::data_representation::Utf8StrRef;
+
+        let buffer = [0x00, 0x02, 0x41, 0x42, 0xFF];
+        let mut reader = ByteReader::new(&buffer);
+
+        let value: Utf8StrRef = reader.read_decoded().unwrap();
+
+        assert_eq!(value.as_str(), "AB");
+        assert_eq!(reader.rest(), &[0xFF]);
+    }
+
+    #[test]
+    fn underflow_leaves_position_unchanged() {
+        let buffer = [0x01, 0x02, 0x03];
+        let mut reader = ByteReader::new(&buffer);
+
+        reader.read_u8().unwrap();
+
+        assert_eq!(
+            reader.read_u32(),
+            Err(DataRepresentationError::BufferUnderflow)
+        );
+        assert_eq!(
+            reader.read_bytes(3),
+            Err(DataRepresentationError::BufferUnderflow)
+        );
+        assert_eq!(reader.position(), 1);
+    }
+
+    #[test]
+    fn truncated_string_leaves_position_unchanged() {
+        let buffer = [0x00, 0x03, 0x41, 0x42];
+        let mut reader = ByteReader::new(&buffer);
+
+        assert_eq!(
+            reader.read_utf8_str(),
+            Err(DataRepresentationError::BufferUnderflow)
+        );
+        assert_eq!(reader.position(), 0);
+    }
+
+    #[test]
+    fn rejects_invalid_string() {
+        let buffer = [0x00, 0x02, 0x41, 0x00];
+        let mut reader = ByteReader::new(&buffer);
+
+        assert_eq!(
+            reader.read_utf8_str(),
+            Err(DataRepresentationError::NullTerminatorInString)
+        );
+        assert_eq!(reader.position(), 0);
+    }
+
+    #[test]
+    fn lenient_reader_accepts_control_characters() {
+        let buffer = [0x00, 0x02, 0x41, 0x01];
+
+        assert_eq!(
+            ByteReader::new(&buffer).read_utf8_str(),
+            Err(DataRepresentationError::ControlCharacterInString)
+        );
+        assert_eq!(
+            ByteReader::with_validation(&buffer, Utf8Validation::Lenient).read_utf8_str(),
+            Ok("A\u{1}")
+        );
+    }
+}
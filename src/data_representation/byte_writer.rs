@@ -0,0 +1,222 @@
+use super::{
+    DataRepresentationError, FourByteInt, MqttEncode, TwoByteInt, Utf8StrRef, Utf8Validation,
+    VariableByteInt,
+};
+
+/// A write cursor over a caller-provided buffer. Each `put_*` either writes the whole
+/// value and advances, or leaves the cursor untouched and returns `BufferOverflow`.
+/// Strings are validated strictly unless the writer is created `with_validation`.
+#[derive(Debug)]
+pub struct ByteWriter<'a> {
+    buffer: &'a mut [u8],
+    position: usize,
+    validation: Utf8Validation,
+}
+
+impl<'a> ByteWriter<'a> {
+    /// Creates a writer positioned at the start of the buffer
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Self::with_validation(buffer, Utf8Validation::Strict)
+    }
+
+    /// Creates a writer positioned at the start of the buffer, validating strings as given
+    pub fn with_validation(buffer: &'a mut [u8], validation: Utf8Validation) -> Self {
+        Self {
+            buffer,
+            position: 0,
+            validation,
+        }
+    }
+
+    /// Returns how strictly strings are validated
+    pub fn validation(&self) -> Utf8Validation {
+        self.validation
+    }
+
+    /// Returns the number of bytes written so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the number of bytes still available in the buffer
+    pub fn remaining(&self) -> usize {
+        self.buffer.len().saturating_sub(self.position)
+    }
+
+    /// Writes a single byte
+    pub fn put_u8(&mut self, value: u8) -> Result<(), DataRepresentationError> {
+        self.put_bytes(&[value])
+    }
+
+    /// Writes a Two Byte Integer (big-endian)
+    pub fn put_u16(&mut self, value: u16) -> Result<(), DataRepresentationError> {
+        self.put_bytes(&TwoByteInt::from(value).to_bytes())
+    }
+
+    /// Writes a Four Byte Integer (big-endian)
+    pub fn put_u32(&mut self, value: u32) -> Result<(), DataRepresentationError> {
+        self.put_bytes(&FourByteInt::from(value).to_bytes())
+    }
+
+    /// Writes the significant bytes of a Variable Byte Integer
+    pub fn put_vbi(&mut self, value: VariableByteInt) -> Result<(), DataRepresentationError> {
+        let bytes = value.to_bytes();
+        self.put_bytes(bytes.get(..value.length()).unwrap_or_default())
+    }
+
+    /// Writes a UTF-8 string: 2 bytes of length data followed by the string itself.
+    /// The string is validated as strictly as the writer was created with; use
+    /// `put_utf8_str` for one already validated
+    pub fn put_utf8_string(&mut self, value: &str) -> Result<(), DataRepresentationError> {
+        self.put_utf8_str(Utf8StrRef::with_validation(value, self.validation)?)
+    }
+
+    /// Writes an already validated UTF-8 string, whatever strictness it was validated with
+    pub fn put_utf8_str(&mut self, utf8_str: Utf8StrRef) -> Result<(), DataRepresentationError> {
+        // check for room up front, so the length is never written without the string
+        if self.remaining() < utf8_str.encoded_len() {
+            return Err(DataRepresentationError::BufferOverflow);
+        }
+
+        // prefix the bytes actually written, never a truncated length
+        let bytes = utf8_str.as_str().as_bytes();
+        let length =
+            u16::try_from(bytes.len()).map_err(|_| DataRepresentationError::Utf8StringTooLong)?;
+
+        self.put_u16(length)?;
+        self.put_bytes(bytes)
+    }
+
+    /// Writes any encodable value, such as a property block, in its wire format
+    pub fn put_encoded<T: MqttEncode>(&mut self, value: &T) -> Result<(), T::Error>
+    where
+        T::Error: From<DataRepresentationError>,
+    {
+        self.put_with(|target| value.encode(target))
+    }
+
+    // writes whatever `encode` puts at the start of the unwritten part of the buffer,
+    // advancing by the length it returns
+    pub(crate) fn put_with<E: From<DataRepresentationError>>(
+        &mut self,
+        encode: impl FnOnce(&mut [u8]) -> Result<usize, E>,
+    ) -> Result<(), E> {
+        let target = self
+            .buffer
+            .get_mut(self.position..)
+            .ok_or(DataRepresentationError::BufferOverflow)?;
+        self.position += encode(target)?;
+
+        Ok(())
+    }
+
+    /// Writes raw bytes, with no length prefix
+    pub fn put_bytes(&mut self, bytes: &[u8]) -> Result<(), DataRepresentationError> {
+        let end = self.position + bytes.len();
+
+        self.buffer
+            .get_mut(self.position..end)
+            .ok_or(DataRepresentationError::BufferOverflow)?
+            .copy_from_slice(bytes);
+        self.position = end;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_byte_writer {
+    use super::*;
+
+    #[test]
+    fn writes_sequentially() {
+        let mut buffer = [0; 11];
+        let mut writer = ByteWriter::new(&mut buffer);
+
+        writer.put_u8(0x10).unwrap();
+        writer.put_u16(0x1234).unwrap();
+        writer.put_vbi(VariableByteInt::new(321).unwrap()).unwrap();
+        writer.put_utf8_string("AB").unwrap();
+
+        assert_eq!(writer.position(), 9);
+        assert_eq!(writer.remaining(), 2);
+        assert_eq!(
+            buffer,
+            [
+                0x10, 0x12, 0x34, 0xC1, 0x02, 0x00, 0x02, 0x41, 0x42, 0x00, 0x00
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_encodable_values() {
+        let mut buffer = [0; 4];
+        let mut writer = ByteWriter::new(&mut buffer);
+
+        writer.put_u8(0x01).unwrap();
+        writer
+            .put_encoded(&VariableByteInt::new(321).unwrap())
+            .unwrap();
+
+        assert_eq!(writer.position(), 3);
+        assert_eq!(buffer, [0x01, 0xC1, 0x02, 0x00]);
+    }
+
+    #[test]
+    fn overflow_leaves_position_unchanged() {
+        let mut buffer = [0; 3];
+        let mut writer = ByteWriter::new(&mut buffer);
+
+        writer.put_u8(0x01).unwrap();
+
+        assert_eq!(
+            writer.put_u32(0x0102_0304),
+            Err(DataRepresentationError::BufferOverflow)
+        );
+        assert_eq!(
+            writer.put_utf8_string("A"),
+            Err(DataRepresentationError::BufferOverflow)
+        );
+        assert_eq!(writer.position(), 1);
+        assert_eq!(writer.remaining(), 2);
+    }
+
+    #[test]
+    fn rejects_invalid_string() {
+        let mut buffer = [0; 8];
+        let mut writer = ByteWriter::new(&mut buffer);
+
+        assert_eq!(
+            writer.put_utf8_string("A\0"),
+            Err(DataRepresentationError::NullTerminatorInString)
+        );
+        assert_eq!(writer.position(), 0);
+    }
+
+    #[test]
+    fn rejects_overlong_string_instead_of_truncating_its_length() {
+        let value = "a".repeat(65_536);
+        let mut buffer = vec![0; 65_540];
+        let mut writer = ByteWriter::new(&mut buffer);
+
+        assert_eq!(
+            writer.put_utf8_str(Utf8StrRef::from_validated(&value)),
+            Err(DataRepresentationError::Utf8StringTooLong)
+        );
+        assert_eq!(writer.position(), 0);
+    }
+
+    #[test]
+    fn lenient_writer_accepts_control_characters() {
+        let mut buffer = [0; 4];
+
+        assert_eq!(
+            ByteWriter::new(&mut buffer).put_utf8_string("A\u{1}"),
+            Err(DataRepresentationError::ControlCharacterInString)
+        );
+
+        let mut writer = ByteWriter::with_validation(&mut buffer, Utf8Validation::Lenient);
+        assert_eq!(writer.put_utf8_string("A\u{1}"), Ok(()));
+        assert_eq!(buffer, [0x00, 0x02, 0x41, 0x01]);
+    }
+}
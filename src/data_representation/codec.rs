@@ -0,0 +1,25 @@
+// common encode/decode interface shared by every representation type,
+// and the building block for packet serialization
+
+/// A value that can be written in its MQTT wire format
+pub trait MqttEncode {
+    type Error;
+
+    /// Encodes the value into the start of the buffer, which may be oversized
+    /// Returns the number of bytes written
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error>;
+
+    /// Returns the number of bytes `encode` writes, so buffers (and the Remaining Length
+    /// of a packet) can be sized without a trial encode
+    fn encoded_len(&self) -> usize;
+}
+
+/// A value that can be read from its MQTT wire format. The lifetime allows
+/// borrowed representations to point into the buffer they were decoded from.
+pub trait MqttDecode<'a>: Sized {
+    type Error;
+
+    /// Decodes a value from the start of the buffer; trailing bytes are left untouched
+    /// Returns the value and the number of bytes it occupied
+    fn decode(buffer: &'a [u8]) -> Result<(Self, usize), Self::Error>;
+}
@@ -1,8 +1,18 @@
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum DataRepresentationError {
+    // cursor errors
+    BufferOverflow,
+    BufferUnderflow,
+
+    // fixed-width integer errors
+    IntegerBufferOverflow,
+    IntegerMalformedBuffer,
+
     // variable-byte integer errors
     MalformedVariableByteInteger,
     VariableByteIntegerOutOfRange,
+    VariableByteIntegerBufferOverflow,
 
     // fixed string errors
     FixedStrBufferOverflow,
@@ -13,4 +23,15 @@ pub enum DataRepresentationError {
     Utf8BufferOverflow,
     Utf8MalformedBuffer,
     InvalidUTF8String,
+    SurrogateInString,
+    ControlCharacterInString,
+    NonCharacterInString,
+
+    // binary data errors
+    BinaryDataTooLong,
+    BinaryBufferOverflow,
+    BinaryMalformedBuffer,
+
+    // user properties errors
+    UserPropertiesFull,
 }
@@ -1,17 +1,29 @@
 // simple heapless string implementation
 
-use super::DataRepresentationError;
-use core::{fmt, str};
+use super::{DataRepresentationError, MqttDecode, MqttEncode, Utf8StrRef};
+use core::{fmt, ops::Deref, str};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// A fixed-capacity string holding up to N bytes of UTF-8.
+///
+/// It backs MQTT strings, whose 2-byte length prefix caps them at 65,535 bytes, so larger
+/// capacities are rejected at compile time:
+///
+/// ```compile_fail
+/// let too_large = midge::data_representation::FixedStr::<65536>::new();
+/// ```
+#[derive(Debug, Clone)]
 pub struct FixedStr<const N: usize> {
     buffer: [u8; N],
     len: usize,
 }
 
+const MAX_STR_LEN: usize = 65535;
+
 impl<const N: usize> FixedStr<N> {
     /// Creates a new, empty FixedStr
     pub const fn new() -> Self {
+        const { assert!(N <= MAX_STR_LEN, "FixedStr capacity exceeds 65,535 bytes") };
+
         Self {
             buffer: [0; N],
             len: 0,
@@ -21,22 +33,56 @@ impl<const N: usize> FixedStr<N> {
     /// Appends a string slice to the buffer, if space allows
     pub fn push_str(&mut self, s: &str) -> Result<(), DataRepresentationError> {
         let bytes = s.as_bytes();
+        let end = self.len + bytes.len();
 
         // ensure buffer is large enough
-        if bytes.len() > N - self.len {
-            return Err(DataRepresentationError::FixedStrBufferOverflow);
-        }
+        let target = self
+            .buffer
+            .get_mut(self.len..end)
+            .ok_or(DataRepresentationError::FixedStrBufferOverflow)?;
 
         // add string bytes to the buffer
-        self.buffer[self.len..self.len + bytes.len()].copy_from_slice(bytes);
-        self.len += bytes.len();
+        target.copy_from_slice(bytes);
+        self.len = end;
 
         Ok(())
     }
 
     /// Returns the string slice of the currently stored UTF-8 data
     pub fn as_str(&self) -> &str {
-        core::str::from_utf8(&self.buffer[..self.len]).unwrap()
+        // the buffer is only ever filled from whole `&str`s, so it always holds valid UTF-8
+        str::from_utf8(self.as_bytes()).unwrap_or_default()
+    }
+
+    /// Returns the currently stored bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        self.buffer.get(..self.len).unwrap_or_default()
+    }
+
+    /// Returns the length of the stored string, in bytes
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the stored string is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the maximum number of bytes the string can hold
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Shortens the string to at most `new_len` bytes. If `new_len` falls inside a
+    /// multi-byte character, that whole character is removed rather than split.
+    pub fn truncate(&mut self, new_len: usize) {
+        let mut new_len = new_len.min(self.len);
+        while !self.as_str().is_char_boundary(new_len) {
+            new_len -= 1;
+        }
+
+        self.len = new_len;
     }
 
     /// clears the buffer
@@ -45,9 +91,140 @@ impl<const N: usize> FixedStr<N> {
     }
 }
 
+impl<const N: usize> TryFrom<&str> for FixedStr<N> {
+    type Error = DataRepresentationError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut fixed_str = Self::new();
+        fixed_str.push_str(value)?;
+
+        Ok(fixed_str)
+    }
+}
+
+impl<const N: usize> Deref for FixedStr<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+// only the stored bytes take part in comparisons; anything past `len` is stale
+impl<const N: usize> PartialEq for FixedStr<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for FixedStr<N> {}
+
+impl<const N: usize> PartialEq<str> for FixedStr<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for FixedStr<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize> fmt::Display for FixedStr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<const N: usize> Default for FixedStr<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<const N: usize> defmt::Format for FixedStr<N> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=str}", self.as_str())
+    }
+}
+
+/// Encoded as an MQTT UTF-8 string: 2 bytes of length data followed by the string
+impl<const N: usize> MqttEncode for FixedStr<N> {
+    type Error = DataRepresentationError;
+
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        Utf8StrRef::new(self.as_str())?.encode(buffer)
+    }
+
+    fn encoded_len(&self) -> usize {
+        2 + self.len()
+    }
+}
+
+impl<const N: usize> MqttDecode<'_> for FixedStr<N> {
+    type Error = DataRepresentationError;
+
+    fn decode(buffer: &[u8]) -> Result<(Self, usize), Self::Error> {
+        let (utf8_str, consumed) = Utf8StrRef::decode(buffer)?;
+
+        let mut fixed_str = Self::new();
+        fixed_str.push_str(utf8_str.as_str())?;
+
+        Ok((fixed_str, consumed))
+    }
+}
+
 /// Implementing the `fmt::Write` trait allows us to use the `write!` macro
 impl<const N: usize> fmt::Write for FixedStr<N> {
     fn write_str(&mut self, s: &str) -> fmt::Result {
         self.push_str(s).map_err(|_| fmt::Error)
     }
 }
+
+#[cfg(test)]
+mod test_fixed_str {
+    use super::*;
+
+    #[test]
+    fn behaves_like_a_str() {
+        let fixed_str = FixedStr::<8>::try_from("hello").unwrap();
+
+        assert_eq!(fixed_str, "hello");
+        assert_eq!(fixed_str.len(), 5);
+        assert_eq!(fixed_str.capacity(), 8);
+        assert_eq!(fixed_str.as_bytes(), b"hello");
+        assert!(fixed_str.starts_with("he")); // via Deref
+    }
+
+    #[test]
+    fn rejects_strings_over_capacity() {
+        assert_eq!(
+            FixedStr::<2>::try_from("abc"),
+            Err(DataRepresentationError::FixedStrBufferOverflow)
+        );
+    }
+
+    #[test]
+    fn truncates_on_char_boundaries() {
+        let mut fixed_str = FixedStr::<8>::try_from("aé").unwrap(); // é is 2 bytes
+
+        fixed_str.truncate(2);
+        assert_eq!(fixed_str, "a");
+
+        fixed_str.truncate(5);
+        assert_eq!(fixed_str, "a");
+
+        fixed_str.truncate(0);
+        assert!(fixed_str.is_empty());
+    }
+
+    #[test]
+    fn ignores_stale_bytes_when_comparing() {
+        let mut truncated = FixedStr::<4>::try_from("abcd").unwrap();
+        truncated.truncate(2);
+
+        assert_eq!(truncated, FixedStr::<4>::try_from("ab").unwrap());
+    }
+}
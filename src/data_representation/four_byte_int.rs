@@ -1,3 +1,5 @@
+use super::{DataRepresentationError, MqttDecode, MqttEncode};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct FourByteInt(u32); // wrapper around a 32-bit integer; unsigned per the spec
 
@@ -9,6 +11,45 @@ impl FourByteInt {
     pub fn to_bytes(self) -> [u8; 4] {
         self.0.to_be_bytes() // big-endian
     }
+
+    pub fn value(self) -> u32 {
+        self.0
+    }
+}
+
+impl From<u32> for FourByteInt {
+    fn from(value: u32) -> Self {
+        Self(value)
+    }
+}
+
+impl MqttEncode for FourByteInt {
+    type Error = DataRepresentationError;
+
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        buffer
+            .get_mut(..4)
+            .ok_or(DataRepresentationError::IntegerBufferOverflow)?
+            .copy_from_slice(&self.to_bytes());
+
+        Ok(4)
+    }
+
+    fn encoded_len(&self) -> usize {
+        4
+    }
+}
+
+impl MqttDecode<'_> for FourByteInt {
+    type Error = DataRepresentationError;
+
+    fn decode(buffer: &[u8]) -> Result<(Self, usize), Self::Error> {
+        let (bytes, _) = buffer
+            .split_first_chunk::<4>()
+            .ok_or(DataRepresentationError::IntegerMalformedBuffer)?;
+
+        Ok((Self::from_bytes(*bytes), 4))
+    }
 }
 
 #[cfg(test)]
@@ -23,4 +64,23 @@ mod test_four_byte_int {
 
         assert_eq!(original, reconstructed);
     }
+
+    #[test]
+    fn encodes_into_buffer() {
+        let mut buffer = [0xFF; 5];
+
+        let encoded_len = FourByteInt::from(0x12345678).encode(&mut buffer).unwrap();
+
+        assert_eq!(encoded_len, 4);
+        assert_eq!(buffer[..4], [0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(buffer[4], 0xFF); // untouched
+    }
+
+    #[test]
+    fn rejects_truncated_buffer() {
+        assert_eq!(
+            FourByteInt::decode(&[0x12]),
+            Err(DataRepresentationError::IntegerMalformedBuffer)
+        );
+    }
 }
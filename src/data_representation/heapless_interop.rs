@@ -0,0 +1,93 @@
+// conversions to and from the `heapless` collections, for projects already built on them.
+// the capacities match on both sides, so only validation (never space) can fail.
+
+use super::{BinaryData, DataRepresentationError, FixedStr, Utf8String};
+
+impl<const N: usize> From<FixedStr<N>> for heapless::String<N> {
+    fn from(value: FixedStr<N>) -> Self {
+        // cannot fail: both hold up to N bytes
+        heapless::String::try_from(value.as_str()).unwrap_or_default()
+    }
+}
+
+impl<const N: usize> From<heapless::String<N>> for FixedStr<N> {
+    fn from(value: heapless::String<N>) -> Self {
+        // cannot fail: both hold up to N bytes
+        FixedStr::try_from(value.as_str()).unwrap_or_default()
+    }
+}
+
+impl<const N: usize> From<Utf8String<N>> for heapless::String<N> {
+    fn from(value: Utf8String<N>) -> Self {
+        // cannot fail: both hold up to N bytes
+        heapless::String::try_from(value.as_str()).unwrap_or_default()
+    }
+}
+
+/// Fails if the string does not pass MQTT UTF-8 validation
+impl<const N: usize> TryFrom<heapless::String<N>> for Utf8String<N> {
+    type Error = DataRepresentationError;
+
+    fn try_from(value: heapless::String<N>) -> Result<Self, Self::Error> {
+        let mut utf8_string = Utf8String::new();
+        utf8_string.set(value.as_str())?;
+
+        Ok(utf8_string)
+    }
+}
+
+impl<const N: usize> From<BinaryData<N>> for heapless::Vec<u8, N> {
+    fn from(value: BinaryData<N>) -> Self {
+        // cannot fail: both hold up to N bytes
+        heapless::Vec::from_slice(value.as_bytes()).unwrap_or_default()
+    }
+}
+
+/// Fails if the data exceeds the spec's length limit
+impl<const N: usize> TryFrom<heapless::Vec<u8, N>> for BinaryData<N> {
+    type Error = DataRepresentationError;
+
+    fn try_from(value: heapless::Vec<u8, N>) -> Result<Self, Self::Error> {
+        let mut binary_data = BinaryData::new();
+        binary_data.set(value.as_slice())?;
+
+        Ok(binary_data)
+    }
+}
+
+#[cfg(test)]
+mod test_heapless_interop {
+    use super::*;
+
+    #[test]
+    fn converts_strings_both_ways() {
+        let original = heapless::String::<8>::try_from("midge").unwrap();
+
+        let utf8_string = Utf8String::<8>::try_from(original.clone()).unwrap();
+        assert_eq!(utf8_string.as_str(), "midge");
+        assert_eq!(heapless::String::from(utf8_string), original);
+
+        let fixed_str = FixedStr::from(original.clone());
+        assert_eq!(fixed_str, "midge");
+        assert_eq!(heapless::String::from(fixed_str), original);
+    }
+
+    #[test]
+    fn rejects_invalid_utf8_string() {
+        let original = heapless::String::<8>::try_from("A\0").unwrap();
+
+        assert_eq!(
+            Utf8String::<8>::try_from(original),
+            Err(DataRepresentationError::NullTerminatorInString)
+        );
+    }
+
+    #[test]
+    fn converts_bytes_both_ways() {
+        let original = heapless::Vec::<u8, 4>::from_slice(&[0xDE, 0xAD]).unwrap();
+
+        let binary_data = BinaryData::try_from(original.clone()).unwrap();
+        assert_eq!(binary_data.as_bytes(), &[0xDE, 0xAD]);
+        assert_eq!(heapless::Vec::from(binary_data), original);
+    }
+}
@@ -1,13 +1,41 @@
+mod binary_data;
+#[cfg(feature = "alloc")]
+mod binary_data_buf;
+mod binary_data_ref;
+mod byte_reader;
+mod byte_writer;
+mod codec;
 mod errors;
 mod fixed_str;
 mod four_byte_int;
+#[cfg(feature = "heapless")]
+mod heapless_interop;
 mod two_byte_int;
+mod user_properties;
+mod utf8_str_ref;
 mod utf8_string;
+#[cfg(feature = "alloc")]
+mod utf8_string_buf;
+mod utf8_string_pair;
+mod utf8_validation;
 mod variable_byte_int;
 
-pub(crate) use errors::DataRepresentationError;
-pub(crate) use fixed_str::FixedStr;
-pub(crate) use four_byte_int::FourByteInt;
-pub(crate) use two_byte_int::TwoByteInt;
-pub(crate) use utf8_string::Utf8String;
-pub(crate) use variable_byte_int::VariableByteInt;
+pub use binary_data::BinaryData;
+#[cfg(feature = "alloc")]
+pub use binary_data_buf::BinaryDataBuf;
+pub use binary_data_ref::BinaryDataRef;
+pub use byte_reader::ByteReader;
+pub use byte_writer::ByteWriter;
+pub use codec::{MqttDecode, MqttEncode};
+pub use errors::DataRepresentationError;
+pub use fixed_str::FixedStr;
+pub use four_byte_int::FourByteInt;
+pub use two_byte_int::TwoByteInt;
+pub use user_properties::UserProperties;
+pub use utf8_str_ref::Utf8StrRef;
+pub use utf8_string::Utf8String;
+#[cfg(feature = "alloc")]
+pub use utf8_string_buf::Utf8StringBuf;
+pub use utf8_string_pair::Utf8StringPair;
+pub use utf8_validation::Utf8Validation;
+pub use variable_byte_int::{VariableByteInt, VariableByteIntDecoder};
@@ -1,3 +1,5 @@
+use super::{DataRepresentationError, MqttDecode, MqttEncode};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct TwoByteInt(u16); // wrapper around a 16-bit integer; unsigned per the spec
 
@@ -21,6 +23,35 @@ impl From<u16> for TwoByteInt {
     }
 }
 
+impl MqttEncode for TwoByteInt {
+    type Error = DataRepresentationError;
+
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        buffer
+            .get_mut(..2)
+            .ok_or(DataRepresentationError::IntegerBufferOverflow)?
+            .copy_from_slice(&self.to_bytes());
+
+        Ok(2)
+    }
+
+    fn encoded_len(&self) -> usize {
+        2
+    }
+}
+
+impl MqttDecode<'_> for TwoByteInt {
+    type Error = DataRepresentationError;
+
+    fn decode(buffer: &[u8]) -> Result<(Self, usize), Self::Error> {
+        let (bytes, _) = buffer
+            .split_first_chunk::<2>()
+            .ok_or(DataRepresentationError::IntegerMalformedBuffer)?;
+
+        Ok((Self::from_bytes(*bytes), 2))
+    }
+}
+
 #[cfg(test)]
 mod test_two_byte_int {
     use super::*;
@@ -33,4 +64,23 @@ mod test_two_byte_int {
 
         assert_eq!(original, reconstructed);
     }
+
+    #[test]
+    fn encodes_into_buffer() {
+        let mut buffer = [0xFF; 3];
+
+        let encoded_len = TwoByteInt::from(0x1234).encode(&mut buffer).unwrap();
+
+        assert_eq!(encoded_len, 2);
+        assert_eq!(buffer[..2], [0x12, 0x34]);
+        assert_eq!(buffer[2], 0xFF); // untouched
+    }
+
+    #[test]
+    fn rejects_truncated_buffer() {
+        assert_eq!(
+            TwoByteInt::decode(&[0x12]),
+            Err(DataRepresentationError::IntegerMalformedBuffer)
+        );
+    }
 }
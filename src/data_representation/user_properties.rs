@@ -0,0 +1,198 @@
+use super::{ByteWriter, DataRepresentationError, MqttEncode, Utf8StringPair};
+use crate::properties::{Property, PropertyIdentifier};
+
+/// A fixed-capacity collection of User Properties: up to N name/value pairs,
+/// each string holding up to S bytes. Names may repeat, as permitted by the spec.
+#[derive(Debug, Clone)]
+pub struct UserProperties<const N: usize, const S: usize> {
+    pairs: [Utf8StringPair<S>; N],
+    len: usize,
+}
+
+impl<const N: usize, const S: usize> UserProperties<N, S> {
+    /// Creates an empty collection
+    pub const fn new() -> Self {
+        Self {
+            pairs: [const { Utf8StringPair::new() }; N],
+            len: 0,
+        }
+    }
+
+    /// Appends a name/value pair, if space allows
+    pub fn push(&mut self, name: &str, value: &str) -> Result<(), DataRepresentationError> {
+        let pair = self
+            .pairs
+            .get_mut(self.len)
+            .ok_or(DataRepresentationError::UserPropertiesFull)?;
+        pair.set(name, value)?;
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Returns the number of pairs held
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the collection holds no pairs
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// removes all pairs
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Iterates over the held pairs, in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = &Utf8StringPair<S>> {
+        self.pairs.iter().take(self.len)
+    }
+
+    /// Returns the value of the first pair with the given name
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.iter()
+            .find(|pair| pair.name() == name)
+            .map(Utf8StringPair::value)
+    }
+
+    /// Iterates over the values of every pair with the given name
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.iter()
+            .filter(move |pair| pair.name() == name)
+            .map(Utf8StringPair::value)
+    }
+
+    /// Iterates over the pairs as `Property::UserProperty` values, for use in a property block
+    pub fn properties(&self) -> impl Iterator<Item = Property<'_>> {
+        self.iter()
+            .map(|pair| Property::UserProperty(pair.name(), pair.value()))
+    }
+}
+
+impl<const N: usize, const S: usize> MqttEncode for UserProperties<N, S> {
+    type Error = DataRepresentationError;
+
+    /// Encodes every pair as a User Property: the identifier followed by a UTF-8 String Pair
+    /// Returns the number of bytes written
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        let mut writer = ByteWriter::new(buffer);
+
+        for pair in self.iter() {
+            writer.put_u8(PropertyIdentifier::UserProperty as u8)?;
+            writer.put_encoded(pair)?;
+        }
+
+        Ok(writer.position())
+    }
+
+    fn encoded_len(&self) -> usize {
+        // each pair is preceded by its single-byte identifier
+        self.iter().map(|pair| 1 + pair.encoded_len()).sum()
+    }
+}
+
+// only the held pairs are compared; slots past `len` keep whatever was cleared from them
+impl<const N: usize, const S: usize> PartialEq for UserProperties<N, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<const N: usize, const S: usize> Eq for UserProperties<N, S> {}
+
+impl<const N: usize, const S: usize> Default for UserProperties<N, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test_user_properties {
+    use super::*;
+
+    #[test]
+    fn looks_up_by_name() {
+        let mut user_properties = UserProperties::<4, 8>::new();
+        user_properties.push("region", "eu").unwrap();
+        user_properties.push("tenant", "a").unwrap();
+        user_properties.push("region", "us").unwrap();
+
+        assert_eq!(user_properties.len(), 3);
+        assert_eq!(user_properties.get("region"), Some("eu"));
+        assert_eq!(user_properties.get("tenant"), Some("a"));
+        assert_eq!(user_properties.get("missing"), None);
+
+        let mut regions = user_properties.get_all("region");
+        assert_eq!(regions.next(), Some("eu"));
+        assert_eq!(regions.next(), Some("us"));
+        assert_eq!(regions.next(), None);
+    }
+
+    #[test]
+    fn rejects_pairs_over_capacity() {
+        let mut user_properties = UserProperties::<1, 8>::new();
+        user_properties.push("a", "b").unwrap();
+
+        assert_eq!(
+            user_properties.push("c", "d"),
+            Err(DataRepresentationError::UserPropertiesFull)
+        );
+        assert_eq!(user_properties.len(), 1);
+    }
+
+    #[test]
+    fn rejects_strings_over_capacity() {
+        let mut user_properties = UserProperties::<2, 2>::new();
+
+        assert_eq!(
+            user_properties.push("abc", "d"),
+            Err(DataRepresentationError::Utf8StringTooLong)
+        );
+        assert!(user_properties.is_empty());
+    }
+
+    #[test]
+    fn encodes_each_pair_as_a_property() {
+        let mut user_properties = UserProperties::<2, 4>::new();
+        user_properties.push("a", "b").unwrap();
+        user_properties.push("c", "").unwrap();
+
+        let mut buffer = [0; 13];
+
+        let expected_buffer = [
+            0x26, // user property identifier
+            0x00, 0x01, 0x61, // name "a"
+            0x00, 0x01, 0x62, // value "b"
+            0x26, // user property identifier
+            0x00, 0x01, 0x63, // name "c"
+            0x00, 0x00, // empty value
+        ];
+
+        let encoded_len = user_properties.encode(&mut buffer).unwrap();
+
+        assert_eq!(encoded_len, 13);
+        assert_eq!(buffer, expected_buffer);
+    }
+
+    #[test]
+    fn converts_to_properties() {
+        let mut user_properties = UserProperties::<2, 4>::new();
+        user_properties.push("a", "b").unwrap();
+
+        let mut properties = user_properties.properties();
+
+        assert_eq!(properties.next(), Some(Property::UserProperty("a", "b")));
+        assert_eq!(properties.next(), None);
+    }
+
+    #[test]
+    fn cleared_collection_equals_new() {
+        let mut user_properties = UserProperties::<2, 4>::new();
+        user_properties.push("a", "b").unwrap();
+        user_properties.clear();
+
+        assert_eq!(user_properties, UserProperties::new());
+    }
+}
@@ -0,0 +1,212 @@
+use super::TwoByteInt;
+use super::{DataRepresentationError, MqttDecode, MqttEncode, Utf8Validation};
+use core::fmt;
+
+/// A borrowed MQTT UTF-8 string, pointing into the buffer it was decoded from.
+/// Unlike `Utf8String`, decoding copies nothing and requires no capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Utf8StrRef<'a> {
+    value: &'a str,
+    length: u16,
+}
+
+const MAX_STR_LEN: usize = 65535;
+
+impl<'a> Utf8StrRef<'a> {
+    /// Wraps a string slice, enforcing strict utf-8 validation per the spec
+    pub fn new(value: &'a str) -> Result<Self, DataRepresentationError> {
+        Self::with_validation(value, Utf8Validation::Strict)
+    }
+
+    /// Wraps a string slice, enforcing utf-8 validation of the given strictness
+    pub fn with_validation(
+        value: &'a str,
+        validation: Utf8Validation,
+    ) -> Result<Self, DataRepresentationError> {
+        if value.len() > MAX_STR_LEN {
+            return Err(DataRepresentationError::Utf8StringTooLong);
+        }
+
+        validation.validate(value)?;
+
+        let length =
+            u16::try_from(value.len()).map_err(|_| DataRepresentationError::Utf8StringTooLong)?;
+
+        Ok(Self { value, length })
+    }
+
+    // wraps a string its owner has already validated, e.g. a `Utf8String` set leniently,
+    // so encoding does not check it again against a different strictness.
+    // the owner guarantees the 65,535 byte limit
+    pub(crate) fn from_validated(value: &'a str) -> Self {
+        Self {
+            value,
+            length: u16::try_from(value.len()).unwrap_or(u16::MAX),
+        }
+    }
+
+    /// Returns the borrowed string slice
+    pub fn as_str(&self) -> &'a str {
+        self.value
+    }
+
+    /// Returns the length of the string in bytes, excluding the 2 bytes of length data
+    pub fn len(&self) -> usize {
+        self.length as usize
+    }
+
+    /// Returns whether the string is empty
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Decodes an MQTT UTF-8 string from a byte buffer, validating it with the given strictness
+    pub fn decode_with_validation(
+        buffer: &'a [u8],
+        validation: Utf8Validation,
+    ) -> Result<(Self, usize), DataRepresentationError> {
+        // Read the length
+        let (length_bytes, rest) = buffer
+            .split_first_chunk::<2>()
+            .ok_or(DataRepresentationError::Utf8MalformedBuffer)?;
+        let len = TwoByteInt::from_bytes(*length_bytes).value() as usize;
+
+        // Ensure the buffer is large enough to hold the supposed number of bytes
+        let utf8_bytes = rest
+            .get(..len)
+            .ok_or(DataRepresentationError::Utf8MalformedBuffer)?;
+
+        // Ensure well-formed UTF-8 with permitted characters
+        let value = validation.parse(utf8_bytes)?;
+
+        Ok((Self::with_validation(value, validation)?, 2 + len))
+    }
+}
+
+impl MqttEncode for Utf8StrRef<'_> {
+    type Error = DataRepresentationError;
+
+    /// Encodes the UTF-8 string into the MQTT-spec format
+    /// Returns the length (including the 2 bytes of length data) of the encoded string
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        let string_bytes = self.value.as_bytes();
+        let encoded_len = 2 + string_bytes.len();
+
+        let (length_bytes, string_target) = buffer
+            .get_mut(..encoded_len)
+            .ok_or(DataRepresentationError::Utf8BufferOverflow)?
+            .split_at_mut(2);
+
+        // Encode length as two-byte integer (in bytes)
+        length_bytes.copy_from_slice(&TwoByteInt::from(self.length).to_bytes());
+
+        // Copy the string data into the buffer
+        string_target.copy_from_slice(string_bytes);
+
+        Ok(encoded_len)
+    }
+
+    /// Returns the length of the encoded string, including the 2 bytes of length data
+    fn encoded_len(&self) -> usize {
+        2 + self.len()
+    }
+}
+
+impl<'a> MqttDecode<'a> for Utf8StrRef<'a> {
+    type Error = DataRepresentationError;
+
+    /// Decodes an MQTT UTF-8 string from a byte buffer, borrowing the string data from it
+    fn decode(buffer: &'a [u8]) -> Result<(Self, usize), Self::Error> {
+        Self::decode_with_validation(buffer, Utf8Validation::Strict)
+    }
+}
+
+impl fmt::Display for Utf8StrRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+#[cfg(test)]
+mod test_utf8_str_ref {
+    use super::*;
+
+    #[test]
+    fn decodes_example_from_spec() {
+        let buffer = [
+            0x00, 0x05, // length of 5 utf-8 chars
+            0x41, // A
+            0xF0, 0xAA, 0x9B, 0x94, // 𪛔
+            0xFF, // trailing byte belonging to the next field
+        ];
+
+        let (utf8_str, consumed) = Utf8StrRef::decode(&buffer).unwrap();
+
+        assert_eq!(utf8_str.as_str(), "A𪛔");
+        assert_eq!(consumed, 7);
+    }
+
+    #[test]
+    fn borrows_from_the_buffer() {
+        let buffer = [0x00, 0x02, 0x41, 0x42];
+
+        let (utf8_str, _) = Utf8StrRef::decode(&buffer).unwrap();
+
+        assert_eq!(utf8_str.as_str().as_ptr(), buffer[2..].as_ptr());
+    }
+
+    #[test]
+    fn encodes_simple_string() {
+        let utf8_str = Utf8StrRef::new("AB").unwrap();
+        let mut buffer = [0; 5];
+
+        let encoded_len = utf8_str.encode(&mut buffer).unwrap();
+
+        assert_eq!(encoded_len, 4);
+        assert_eq!(buffer, [0x00, 0x02, 0x41, 0x42, 0x00]);
+    }
+
+    #[test]
+    fn rejects_null_terminator() {
+        let buffer = [0x00, 0x02, 0x41, 0x00];
+
+        assert_eq!(
+            Utf8StrRef::decode(&buffer).map(|(utf8_str, _)| utf8_str),
+            Err(DataRepresentationError::NullTerminatorInString)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_utf8() {
+        let buffer = [0x00, 0x02, 0xC3, 0x28];
+
+        assert_eq!(
+            Utf8StrRef::decode(&buffer).map(|(utf8_str, _)| utf8_str),
+            Err(DataRepresentationError::InvalidUTF8String)
+        );
+    }
+
+    #[test]
+    fn lenient_decode_accepts_control_characters() {
+        let buffer = [0x00, 0x02, 0x41, 0x09];
+
+        assert_eq!(
+            Utf8StrRef::decode(&buffer).map(|(utf8_str, _)| utf8_str),
+            Err(DataRepresentationError::ControlCharacterInString)
+        );
+
+        let (utf8_str, _) =
+            Utf8StrRef::decode_with_validation(&buffer, Utf8Validation::Lenient).unwrap();
+        assert_eq!(utf8_str.as_str(), "A\t");
+    }
+
+    #[test]
+    fn rejects_truncated_buffer() {
+        let buffer = [0x00, 0x03, 0x41, 0x42];
+
+        assert_eq!(
+            Utf8StrRef::decode(&buffer).map(|(utf8_str, _)| utf8_str),
+            Err(DataRepresentationError::Utf8MalformedBuffer)
+        );
+    }
+}
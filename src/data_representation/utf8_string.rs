@@ -1,107 +1,193 @@
-use super::TwoByteInt;
-use super::{DataRepresentationError, FixedStr};
+use super::{ByteWriter, DataRepresentationError, FixedStr, MqttDecode, MqttEncode};
+use super::{Utf8StrRef, Utf8Validation};
 use core::{fmt, str};
 
+/// A fixed-capacity MQTT UTF-8 string holding up to N bytes.
+///
+/// The 2-byte length prefix caps a string at 65,535 bytes, so larger capacities are
+/// rejected at compile time:
+///
+/// ```compile_fail
+/// let too_large = midge::data_representation::Utf8String::<65536>::new();
+/// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Utf8String<const N: usize> {
     value: FixedStr<N>,
     length: u16,
 }
 
-const MAX_STR_LEN: u16 = 65535;
+const MAX_STR_LEN: usize = 65535;
 
 impl<const N: usize> Utf8String<N> {
     /// Creates an empty utf-8 string
     pub const fn new() -> Self {
+        const { assert!(N <= MAX_STR_LEN, "Utf8String capacity exceeds 65,535 bytes") };
+
         Self {
             value: FixedStr::new(),
             length: 0,
         }
     }
 
-    /// Sets the value of the string, enforcing utf-8 validation per the spec
+    /// Sets the value of the string, enforcing strict utf-8 validation per the spec
     pub fn set(&mut self, value: &str) -> Result<(), DataRepresentationError> {
-        if value.len() > N {
+        self.set_with_validation(value, Utf8Validation::Strict)
+    }
+
+    /// Sets the value of the string, enforcing utf-8 validation of the given strictness
+    pub fn set_with_validation(
+        &mut self,
+        value: &str,
+        validation: Utf8Validation,
+    ) -> Result<(), DataRepresentationError> {
+        // limit the string length to the capacity and the maximum permitted by the spec
+        if value.len() > N || value.len() > MAX_STR_LEN {
             return Err(DataRepresentationError::Utf8StringTooLong);
         }
 
-        if value.contains('\0') {
-            return Err(DataRepresentationError::NullTerminatorInString);
-        }
+        validation.validate(value)?;
+
+        let length =
+            u16::try_from(value.len()).map_err(|_| DataRepresentationError::Utf8StringTooLong)?;
 
         // UTF-8 encoding check is handled by Rust's str type
         self.value.clear();
-        match self.value.push_str(value) {
-            Ok(_) => {
-                self.length = value.len() as u16;
-                Ok(())
-            }
-            Err(e) => Err(e),
-        }
+        self.value.push_str(value)?;
+        self.length = length;
+
+        Ok(())
     }
 
-    /// Encodes the UTF-8 string into the MQTT-spec format
-    /// Returns the length (including the 2 bytes of length data) of the encoded string
-    pub fn encode(&self, buffer: &mut [u8]) -> Result<u16, DataRepresentationError> {
-        // limit the string length to the maximum permitted by the spec
-        if self.length > MAX_STR_LEN {
-            return Err(DataRepresentationError::Utf8StringTooLong);
-        }
+    /// Returns the string slice of the stored value
+    pub fn as_str(&self) -> &str {
+        self.value.as_str()
+    }
 
-        // Ensure the buffer is large enough (remember, we have 2 bytes of 'length' to encode)
-        if buffer.len() < (self.length + 2) as usize {
-            return Err(DataRepresentationError::Utf8BufferOverflow);
-        }
+    /// Returns the length of the encoded string, excluding the 2 bytes of length data
+    pub fn len(&self) -> usize {
+        self.length as usize
+    }
 
-        // Encode length as two-byte integer (in bytes)
-        let length_bytes = TwoByteInt::from(self.length).to_bytes();
+    /// Returns whether the string is empty
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+}
 
-        // Copy the length bytes into the buffer
-        buffer[..2].copy_from_slice(&length_bytes);
+impl<const N: usize> MqttEncode for Utf8String<N> {
+    type Error = DataRepresentationError;
 
-        // Copy the string data into the buffer
-        // Use the length to only copy the necessary bytes; this allows the caller to
-        // provide an oversized buffer, which avoids them knowing the internal representation.
-        buffer[2..2 + self.length as usize].copy_from_slice(self.value.as_str().as_bytes());
+    /// Encodes the UTF-8 string into the MQTT-spec format
+    /// Returns the length (including the 2 bytes of length data) of the encoded string
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        // Only the necessary bytes are written; this allows the caller to provide an
+        // oversized buffer, which avoids them knowing the internal representation.
+        let mut writer = ByteWriter::new(buffer);
+        // the value was validated when set, possibly leniently, so it is not checked again
+        writer.put_utf8_str(Utf8StrRef::from_validated(self.value.as_str()))?;
+
+        Ok(writer.position())
+    }
 
-        Ok(2 + self.length)
+    fn encoded_len(&self) -> usize {
+        2 + self.len()
     }
+}
+
+impl<const N: usize> MqttDecode<'_> for Utf8String<N> {
+    type Error = DataRepresentationError;
 
     /// Decodes an MQTT UTF-8 string from a byte buffer
-    pub fn decode(buffer: &[u8]) -> Result<Self, DataRepresentationError> {
-        if buffer.len() < 2 {
-            return Err(DataRepresentationError::Utf8MalformedBuffer);
-        }
+    fn decode(buffer: &[u8]) -> Result<(Self, usize), Self::Error> {
+        // length, UTF-8 and null-terminator checks are shared with the borrowed representation
+        let (utf8_str, consumed) = Utf8StrRef::decode(buffer)?;
 
-        // Read the length
-        let len = TwoByteInt::from_bytes([buffer[0], buffer[1]]).value() as usize;
+        let mut utf8_string = Utf8String::new();
+        utf8_string.set(utf8_str.as_str())?;
 
-        // Ensure the buffer is large enough to hold the supposed number of bytes
-        if len + 2 > buffer.len() {
-            return Err(DataRepresentationError::Utf8MalformedBuffer);
-        }
+        Ok((utf8_string, consumed))
+    }
+}
 
-        let utf8_bytes = &buffer[2..2 + len];
+impl<const N: usize> Default for Utf8String<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        // Ensure valid UTF-8
-        let utf8_str = core::str::from_utf8(utf8_bytes)
-            .map_err(|_| DataRepresentationError::InvalidUTF8String)?;
+impl<const N: usize> fmt::Display for Utf8String<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value.as_str())
+    }
+}
 
-        // Ensure no null-terminators
-        if utf8_str.contains('\0') {
-            return Err(DataRepresentationError::NullTerminatorInString);
-        }
+// keeps the longest prefix of the arbitrary string that fits and passes strict
+// validation, so every generated value round-trips
+#[cfg(feature = "arbitrary")]
+impl<'a, const N: usize> arbitrary::Arbitrary<'a> for Utf8String<N> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let candidate: &str = u.arbitrary()?;
+
+        let end = candidate
+            .char_indices()
+            .map(|(start, c)| (start, start + c.len_utf8()))
+            .take_while(|&(start, end)| {
+                end <= N.min(MAX_STR_LEN)
+                    && candidate
+                        .get(start..end)
+                        .is_some_and(|c| Utf8Validation::Strict.validate(c).is_ok())
+            })
+            .last()
+            .map_or(0, |(_, end)| end);
 
         let mut utf8_string = Utf8String::new();
-        utf8_string.set(utf8_str)?;
+        utf8_string
+            .set(candidate.get(..end).unwrap_or_default())
+            .map_err(|_| arbitrary::Error::IncorrectFormat)?;
 
         Ok(utf8_string)
     }
 }
 
-impl<const N: usize> fmt::Display for Utf8String<N> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.value.as_str())
+#[cfg(feature = "defmt")]
+impl<const N: usize> defmt::Format for Utf8String<N> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=str}", self.as_str())
+    }
+}
+
+/// Serialized as a plain string
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for Utf8String<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Deserialized from a plain string, which must pass the same validation as `set`
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for Utf8String<N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Utf8StringVisitor<const N: usize>;
+
+        impl<const N: usize> serde::de::Visitor<'_> for Utf8StringVisitor<N> {
+            type Value = Utf8String<N>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a UTF-8 string of at most {N} bytes")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                let mut utf8_string = Utf8String::new();
+                utf8_string
+                    .set(value)
+                    .map_err(|error| E::custom(format_args!("{error:?}")))?;
+
+                Ok(utf8_string)
+            }
+        }
+
+        deserializer.deserialize_str(Utf8StringVisitor)
     }
 }
 
@@ -137,7 +223,7 @@ mod test_utf8_str {
             0x42, // B
         ];
 
-        let utf8_str = Utf8String::<2>::decode(&buffer).unwrap();
+        let (utf8_str, _) = Utf8String::<2>::decode(&buffer).unwrap();
 
         assert_eq!(utf8_str.value.as_str(), "AB");
     }
@@ -170,8 +256,44 @@ mod test_utf8_str {
             0xF0, 0xAA, 0x9B, 0x94, // 𪛔
         ];
 
-        let utf8_str = Utf8String::<16>::decode(&buffer).unwrap();
+        let (utf8_str, _) = Utf8String::<16>::decode(&buffer).unwrap();
 
         assert_eq!(utf8_str.value.as_str(), "A𪛔");
     }
+
+    #[test]
+    fn lenient_value_round_trips() {
+        use crate::data_representation::ByteReader;
+
+        let mut utf8_str = Utf8String::<8>::new();
+        utf8_str
+            .set_with_validation("a\u{1}", Utf8Validation::Lenient)
+            .unwrap();
+
+        let mut buffer = [0; 4];
+        assert_eq!(utf8_str.encode(&mut buffer), Ok(4));
+        assert_eq!(buffer, [0x00, 0x02, 0x61, 0x01]);
+
+        let mut reader = ByteReader::with_validation(&buffer, Utf8Validation::Lenient);
+        assert_eq!(reader.read_utf8_str(), Ok(utf8_str.as_str()));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test_utf8_string_serde {
+    use super::*;
+    use serde_test::{Token, assert_de_tokens_error, assert_tokens};
+
+    #[test]
+    fn round_trips_as_a_string() {
+        let mut utf8_string = Utf8String::<8>::new();
+        utf8_string.set("midge").unwrap();
+
+        assert_tokens(&utf8_string, &[Token::Str("midge")]);
+    }
+
+    #[test]
+    fn rejects_invalid_strings() {
+        assert_de_tokens_error::<Utf8String<2>>(&[Token::Str("abc")], "Utf8StringTooLong");
+    }
 }
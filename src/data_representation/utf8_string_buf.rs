@@ -0,0 +1,152 @@
+use super::{ByteWriter, DataRepresentationError, MqttDecode, MqttEncode};
+use super::{Utf8StrRef, Utf8Validation};
+use alloc::string::String;
+use core::fmt;
+
+/// A heap-backed MQTT UTF-8 string, for hosted environments where a fixed capacity
+/// is awkward. Enforces the same validation as `Utf8String`, up to the spec's limit.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Utf8StringBuf {
+    value: String,
+}
+
+impl Utf8StringBuf {
+    /// Creates an empty utf-8 string
+    pub const fn new() -> Self {
+        Self {
+            value: String::new(),
+        }
+    }
+
+    /// Sets the value of the string, enforcing strict utf-8 validation per the spec
+    pub fn set(&mut self, value: &str) -> Result<(), DataRepresentationError> {
+        self.set_with_validation(value, Utf8Validation::Strict)
+    }
+
+    /// Sets the value of the string, enforcing utf-8 validation of the given strictness
+    pub fn set_with_validation(
+        &mut self,
+        value: &str,
+        validation: Utf8Validation,
+    ) -> Result<(), DataRepresentationError> {
+        Utf8StrRef::with_validation(value, validation)?;
+
+        self.value.clear();
+        self.value.push_str(value);
+
+        Ok(())
+    }
+
+    /// Returns the string slice of the stored value
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    /// Returns the length of the string in bytes, excluding the 2 bytes of length data
+    pub fn len(&self) -> usize {
+        self.value.len()
+    }
+
+    /// Returns whether the string is empty
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    /// Unwraps the underlying `String`
+    pub fn into_string(self) -> String {
+        self.value
+    }
+}
+
+impl TryFrom<&str> for Utf8StringBuf {
+    type Error = DataRepresentationError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut utf8_string = Self::new();
+        utf8_string.set(value)?;
+
+        Ok(utf8_string)
+    }
+}
+
+/// Takes ownership of the `String` without copying, once it passes validation
+impl TryFrom<String> for Utf8StringBuf {
+    type Error = DataRepresentationError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Utf8StrRef::new(&value)?;
+
+        Ok(Self { value })
+    }
+}
+
+impl MqttEncode for Utf8StringBuf {
+    type Error = DataRepresentationError;
+
+    /// Encodes the UTF-8 string into the MQTT-spec format
+    /// Returns the length (including the 2 bytes of length data) of the encoded string
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        let mut writer = ByteWriter::new(buffer);
+        // the value was validated when set, possibly leniently, so it is not checked again
+        writer.put_utf8_str(Utf8StrRef::from_validated(&self.value))?;
+
+        Ok(writer.position())
+    }
+
+    fn encoded_len(&self) -> usize {
+        2 + self.len()
+    }
+}
+
+impl MqttDecode<'_> for Utf8StringBuf {
+    type Error = DataRepresentationError;
+
+    /// Decodes an MQTT UTF-8 string from a byte buffer
+    fn decode(buffer: &[u8]) -> Result<(Self, usize), Self::Error> {
+        // validation is shared with the borrowed representation
+        let (utf8_str, consumed) = Utf8StrRef::decode(buffer)?;
+
+        let value = String::from(utf8_str.as_str());
+
+        Ok((Self { value }, consumed))
+    }
+}
+
+impl fmt::Display for Utf8StringBuf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.value)
+    }
+}
+
+#[cfg(test)]
+mod test_utf8_string_buf {
+    use super::*;
+
+    #[test]
+    fn test_reversibility() {
+        let original = Utf8StringBuf::try_from("a string with no fixed capacity").unwrap();
+
+        let mut buffer = [0; 40];
+        let encoded_len = original.encode(&mut buffer).unwrap();
+        let (decoded, consumed) = Utf8StringBuf::decode(&buffer).unwrap();
+
+        assert_eq!(encoded_len, 33);
+        assert_eq!(original.encoded_len(), 33);
+        assert_eq!(consumed, 33);
+        assert_eq!(original, decoded);
+    }
+
+    #[test]
+    fn rejects_invalid_strings() {
+        assert_eq!(
+            Utf8StringBuf::try_from(String::from("A\0")),
+            Err(DataRepresentationError::NullTerminatorInString)
+        );
+
+        let too_long = "a".repeat(65536);
+        assert_eq!(
+            Utf8StringBuf::try_from(too_long.as_str()),
+            Err(DataRepresentationError::Utf8StringTooLong)
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use super::{ByteWriter, DataRepresentationError, MqttDecode, MqttEncode, Utf8String};
+
+/// A UTF-8 String Pair: two UTF-8 strings back to back, used for name/value pairs
+/// such as User Properties. Each string may hold up to N bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Utf8StringPair<const N: usize> {
+    name: Utf8String<N>,
+    value: Utf8String<N>,
+}
+
+impl<const N: usize> Utf8StringPair<N> {
+    /// Creates a pair of empty strings
+    pub const fn new() -> Self {
+        Self {
+            name: Utf8String::new(),
+            value: Utf8String::new(),
+        }
+    }
+
+    /// Sets both strings of the pair, enforcing utf-8 validation per the spec.
+    /// The pair is left unchanged if either string is rejected.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), DataRepresentationError> {
+        let mut new_name = Utf8String::new();
+        new_name.set(name)?;
+
+        let mut new_value = Utf8String::new();
+        new_value.set(value)?;
+
+        self.name = new_name;
+        self.value = new_value;
+
+        Ok(())
+    }
+
+    /// Getter for the name string
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Getter for the value string
+    pub fn value(&self) -> &str {
+        self.value.as_str()
+    }
+}
+
+impl<const N: usize> MqttEncode for Utf8StringPair<N> {
+    type Error = DataRepresentationError;
+
+    /// Encodes the string pair into the MQTT-spec format
+    /// Returns the length (including both sets of 2 bytes of length data) of the encoded pair
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        let mut writer = ByteWriter::new(buffer);
+        writer.put_utf8_string(self.name())?;
+        writer.put_utf8_string(self.value())?;
+
+        Ok(writer.position())
+    }
+
+    fn encoded_len(&self) -> usize {
+        4 + self.name().len() + self.value().len()
+    }
+}
+
+impl<const N: usize> MqttDecode<'_> for Utf8StringPair<N> {
+    type Error = DataRepresentationError;
+
+    /// Decodes an MQTT UTF-8 string pair from a byte buffer
+    fn decode(buffer: &[u8]) -> Result<(Self, usize), Self::Error> {
+        let (name, name_len) = Utf8String::decode(buffer)?;
+
+        // the value immediately follows the name
+        let value_buffer = buffer
+            .get(name_len..)
+            .ok_or(DataRepresentationError::Utf8MalformedBuffer)?;
+        let (value, value_len) = Utf8String::decode(value_buffer)?;
+
+        Ok((Self { name, value }, name_len + value_len))
+    }
+}
+
+impl<const N: usize> Default for Utf8StringPair<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test_utf8_string_pair {
+    use super::*;
+
+    #[test]
+    fn encodes_simple_pair() {
+        let mut pair = Utf8StringPair::<4>::new();
+        pair.set("AB", "C").unwrap();
+
+        let mut buffer = [0; 7];
+
+        let expected_buffer = [
+            0x00, 0x02, // name length of 2 utf-8 chars
+            0x41, // A
+            0x42, // B
+            0x00, 0x01, // value length of 1 utf-8 char
+            0x43, // C
+        ];
+
+        let encoded_len = pair.encode(&mut buffer).unwrap();
+
+        assert_eq!(encoded_len, 7);
+        assert_eq!(buffer, expected_buffer);
+    }
+
+    #[test]
+    fn decodes_simple_pair() {
+        let buffer = [
+            0x00, 0x02, // name length of 2 utf-8 chars
+            0x41, // A
+            0x42, // B
+            0x00, 0x01, // value length of 1 utf-8 char
+            0x43, // C
+        ];
+
+        let (pair, _) = Utf8StringPair::<4>::decode(&buffer).unwrap();
+
+        assert_eq!(pair.name(), "AB");
+        assert_eq!(pair.value(), "C");
+    }
+
+    #[test]
+    fn encodes_example_from_spec() {
+        let mut pair = Utf8StringPair::<8>::new();
+        pair.set("A𪛔", "A𪛔").unwrap();
+
+        let mut buffer = [0; 14];
+
+        let expected_buffer = [
+            0x00, 0x05, // name length of 5 utf-8 chars
+            0x41, // A
+            0xF0, 0xAA, 0x9B, 0x94, // 𪛔
+            0x00, 0x05, // value length of 5 utf-8 chars
+            0x41, // A
+            0xF0, 0xAA, 0x9B, 0x94, // 𪛔
+        ];
+
+        pair.encode(&mut buffer).unwrap();
+
+        assert_eq!(buffer, expected_buffer);
+    }
+
+    #[test]
+    fn decodes_empty_value() {
+        let buffer = [
+            0x00, 0x01, // name length of 1 utf-8 char
+            0x41, // A
+            0x00, 0x00, // empty value
+        ];
+
+        let (pair, _) = Utf8StringPair::<4>::decode(&buffer).unwrap();
+
+        assert_eq!(pair.name(), "A");
+        assert_eq!(pair.value(), "");
+    }
+
+    #[test]
+    fn rejects_missing_value() {
+        let buffer = [
+            0x00, 0x01, // name length of 1 utf-8 char
+            0x41, // A
+        ];
+
+        assert_eq!(
+            Utf8StringPair::<4>::decode(&buffer).map(|(pair, _)| pair),
+            Err(DataRepresentationError::Utf8MalformedBuffer)
+        );
+    }
+
+    #[test]
+    fn rejects_undersized_encode_buffer() {
+        let mut pair = Utf8StringPair::<4>::new();
+        pair.set("AB", "C").unwrap();
+
+        let mut buffer = [0; 6];
+
+        assert_eq!(
+            pair.encode(&mut buffer),
+            Err(DataRepresentationError::BufferOverflow)
+        );
+    }
+
+    #[test]
+    fn set_is_atomic_on_error() {
+        let mut pair = Utf8StringPair::<2>::new();
+        pair.set("A", "B").unwrap();
+
+        assert!(pair.set("C", "too long").is_err());
+        assert_eq!(pair.name(), "A");
+        assert_eq!(pair.value(), "B");
+    }
+}
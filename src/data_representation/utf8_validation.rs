@@ -0,0 +1,126 @@
+// validation of the character data of UTF-8 strings, per [MQTT-1.5.4]
+
+use super::DataRepresentationError;
+
+/// How strictly the character data of a UTF-8 string is checked.
+///
+/// Both modes reject what the spec forbids outright: ill-formed UTF-8 (including
+/// UTF-16 surrogates encoded as UTF-8) and U+0000. `Strict` additionally rejects the
+/// code points the spec says SHOULD NOT appear: the control characters U+0001–U+001F
+/// and U+007F–U+009F, and the Unicode non-characters (e.g. U+FFFF).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Utf8Validation {
+    #[default]
+    Strict,
+    Lenient,
+}
+
+impl Utf8Validation {
+    /// Checks the characters of an already well-formed string
+    pub fn validate(self, value: &str) -> Result<(), DataRepresentationError> {
+        for c in value.chars() {
+            if c == '\0' {
+                return Err(DataRepresentationError::NullTerminatorInString);
+            }
+
+            if self == Utf8Validation::Lenient {
+                continue;
+            }
+
+            if c.is_control() {
+                return Err(DataRepresentationError::ControlCharacterInString);
+            }
+
+            if is_noncharacter(c) {
+                return Err(DataRepresentationError::NonCharacterInString);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Converts received bytes to a string, then checks its characters
+    pub fn parse(self, bytes: &[u8]) -> Result<&str, DataRepresentationError> {
+        let value = core::str::from_utf8(bytes).map_err(|error| {
+            // a surrogate half (U+D800–U+DFFF) is encoded with the lead byte 0xED followed by 0xA0–0xBF
+            let invalid = bytes.get(error.valid_up_to()..).unwrap_or_default();
+            match invalid {
+                [0xED, 0xA0..=0xBF, ..] => DataRepresentationError::SurrogateInString,
+                _ => DataRepresentationError::InvalidUTF8String,
+            }
+        })?;
+
+        self.validate(value)?;
+
+        Ok(value)
+    }
+}
+
+// U+FDD0–U+FDEF, plus the last two code points of every plane
+fn is_noncharacter(c: char) -> bool {
+    let code_point = c as u32;
+
+    (0xFDD0..=0xFDEF).contains(&code_point) || (code_point & 0xFFFE) == 0xFFFE
+}
+
+#[cfg(test)]
+mod test_utf8_validation {
+    use super::*;
+
+    #[test]
+    fn accepts_ordinary_text() {
+        assert_eq!(Utf8Validation::Strict.validate("A𪛔 ünïcödé\u{A0}"), Ok(()));
+    }
+
+    #[test]
+    fn rejects_null_in_both_modes() {
+        for mode in [Utf8Validation::Strict, Utf8Validation::Lenient] {
+            assert_eq!(
+                mode.validate("A\0"),
+                Err(DataRepresentationError::NullTerminatorInString)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_control_characters_when_strict() {
+        for value in ["\u{01}", "\t", "\u{1F}", "\u{7F}", "\u{9F}"] {
+            assert_eq!(
+                Utf8Validation::Strict.validate(value),
+                Err(DataRepresentationError::ControlCharacterInString)
+            );
+            assert_eq!(Utf8Validation::Lenient.validate(value), Ok(()));
+        }
+    }
+
+    #[test]
+    fn rejects_noncharacters_when_strict() {
+        for value in ["\u{FDD0}", "\u{FFFE}", "\u{FFFF}", "\u{10FFFF}"] {
+            assert_eq!(
+                Utf8Validation::Strict.validate(value),
+                Err(DataRepresentationError::NonCharacterInString)
+            );
+            assert_eq!(Utf8Validation::Lenient.validate(value), Ok(()));
+        }
+    }
+
+    #[test]
+    fn rejects_surrogates_in_both_modes() {
+        let bytes = [0x41, 0xED, 0xA0, 0x80]; // "A" followed by U+D800
+
+        for mode in [Utf8Validation::Strict, Utf8Validation::Lenient] {
+            assert_eq!(
+                mode.parse(&bytes),
+                Err(DataRepresentationError::SurrogateInString)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_ill_formed_bytes() {
+        assert_eq!(
+            Utf8Validation::Lenient.parse(&[0xC3, 0x28]),
+            Err(DataRepresentationError::InvalidUTF8String)
+        );
+    }
+}
@@ -1,4 +1,5 @@
-use crate::data_representation::DataRepresentationError;
+use super::{DataRepresentationError, MqttDecode, MqttEncode};
+use core::task::Poll;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct VariableByteInt {
@@ -43,12 +44,11 @@ impl VariableByteInt {
     }
 
     /// Encodes the value into a `VariableByteInt` format
-    pub fn encode(self) -> [u8; 4] {
+    pub fn to_bytes(self) -> [u8; 4] {
         let mut x = self.value;
         let mut output = [0u8; 4];
-        let mut i = 0;
 
-        loop {
+        for slot in output.iter_mut() {
             let mut byte = (x % 128) as u8; // least-significant 7 bits
             x /= 128;
 
@@ -56,8 +56,7 @@ impl VariableByteInt {
                 byte |= 0b1000_0000; // set the continuation bit; more data follows
             }
 
-            output[i] = byte;
-            i += 1;
+            *slot = byte;
 
             if x == 0 {
                 break;
@@ -66,27 +65,40 @@ impl VariableByteInt {
 
         output // return the full buffer; the caller must know the actual length
     }
+}
 
-    /// Decodes from a Variable Byte Integer byte sequence
-    pub fn decode(input: &[u8]) -> Result<Self, DataRepresentationError> {
-        let mut multiplier = 1;
-        let mut value: u32 = 0;
-        let mut length = 0;
+impl MqttEncode for VariableByteInt {
+    type Error = DataRepresentationError;
 
-        // silently ignore extra bytes, length must be 4 or fewer
-        for &byte in input.iter().take(4) {
-            let digit = (byte & 127) as u32;
-            value += digit * multiplier;
+    /// Writes only the `length()` significant bytes of the encoding
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        let bytes = self.to_bytes();
 
-            if multiplier > 128 * 128 * 128 {
-                return Err(DataRepresentationError::MalformedVariableByteInteger);
-            }
+        let (target, source) = buffer
+            .get_mut(..self.length)
+            .zip(bytes.get(..self.length))
+            .ok_or(DataRepresentationError::VariableByteIntegerBufferOverflow)?;
+        target.copy_from_slice(source);
+
+        Ok(self.length)
+    }
+
+    fn encoded_len(&self) -> usize {
+        self.length
+    }
+}
 
-            multiplier *= 128;
-            length += 1;
+impl MqttDecode<'_> for VariableByteInt {
+    type Error = DataRepresentationError;
 
-            if (byte & 128) == 0 {
-                return Ok(VariableByteInt { value, length }); // no more data
+    /// Decodes from a Variable Byte Integer byte sequence
+    fn decode(buffer: &[u8]) -> Result<(Self, usize), Self::Error> {
+        let mut decoder = VariableByteIntDecoder::new();
+
+        // silently ignore extra bytes, length must be 4 or fewer
+        for &byte in buffer.iter().take(4) {
+            if let Poll::Ready(result) = decoder.push(byte) {
+                return result.map(|decoded| (decoded, decoded.length));
             }
         }
 
@@ -94,6 +106,74 @@ impl VariableByteInt {
     }
 }
 
+// generates any representable value, so every result round-trips
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for VariableByteInt {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let value = u.int_in_range(0..=Self::MAX_VALUE)?;
+        Self::new(value).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+/// Incremental Variable Byte Integer decoder, for callers receiving the encoding
+/// one byte at a time (e.g. the Remaining Length read straight from a socket).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VariableByteIntDecoder {
+    value: u32,
+    multiplier: u32,
+    length: usize,
+}
+
+impl VariableByteIntDecoder {
+    /// Creates a decoder awaiting the first byte
+    pub const fn new() -> Self {
+        Self {
+            value: 0,
+            multiplier: 1,
+            length: 0,
+        }
+    }
+
+    /// Feeds the next byte of the encoding.
+    /// Returns `Poll::Pending` while more bytes are needed, and `Poll::Ready` once the final
+    /// byte arrives or the encoding is found to be malformed; the decoder is then reset for reuse.
+    pub fn push(&mut self, byte: u8) -> Poll<Result<VariableByteInt, DataRepresentationError>> {
+        let digit = (byte & 127) as u32;
+        self.value += digit * self.multiplier;
+        self.length += 1;
+
+        if (byte & 128) == 0 {
+            let decoded = VariableByteInt {
+                value: self.value,
+                length: self.length,
+            };
+            *self = Self::new();
+            return Poll::Ready(Ok(decoded)); // no more data
+        }
+
+        // a continuation bit on the 4th byte would require a 5th, which the spec forbids
+        if self.length == 4 {
+            *self = Self::new();
+            return Poll::Ready(Err(DataRepresentationError::MalformedVariableByteInteger));
+        }
+
+        self.multiplier *= 128;
+
+        Poll::Pending
+    }
+
+    /// Returns the number of bytes consumed so far by an in-progress decode
+    pub fn bytes_consumed(&self) -> usize {
+        self.length
+    }
+}
+
+impl Default for VariableByteIntDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod test_variable_byte_int {
     use super::*;
@@ -101,7 +181,7 @@ mod test_variable_byte_int {
     #[test]
     fn test_simple_encode() {
         let variable_byte_int = VariableByteInt::new(25).unwrap();
-        let encoded = variable_byte_int.encode();
+        let encoded = variable_byte_int.to_bytes();
         let expected = [25, 0, 0, 0]; // 25 in the least significant byte
 
         assert_eq!(encoded, expected);
@@ -110,29 +190,117 @@ mod test_variable_byte_int {
     #[test]
     fn test_simple_decode() {
         let encoded = [25, 0, 0, 0];
-        let decoded = VariableByteInt::decode(&encoded).unwrap();
+        let (decoded, consumed) = VariableByteInt::decode(&encoded).unwrap();
         let expected = VariableByteInt::new(25).unwrap();
 
         assert_eq!(decoded, expected);
+        assert_eq!(consumed, 1);
     }
 
     #[test]
     fn test_reversibility() {
         let original = VariableByteInt::new(0x69420).unwrap();
-        let encoded = original.encode();
-        let decoded = VariableByteInt::decode(&encoded).unwrap();
+        let encoded = original.to_bytes();
+        let (decoded, _) = VariableByteInt::decode(&encoded).unwrap();
 
         assert_eq!(original, decoded);
         assert_eq!(decoded.length(), 3); // should be the full length
     }
 
+    #[test]
+    fn encodes_only_significant_bytes() {
+        let variable_byte_int = VariableByteInt::new(321).unwrap();
+        let mut buffer = [0xFF; 3];
+
+        let encoded_len = variable_byte_int.encode(&mut buffer).unwrap();
+
+        assert_eq!(encoded_len, 2);
+        assert_eq!(buffer, [0xC1, 0x02, 0xFF]);
+    }
+
+    #[test]
+    fn rejects_undersized_encode_buffer() {
+        let variable_byte_int = VariableByteInt::new(321).unwrap();
+        let mut buffer = [0; 1];
+
+        assert_eq!(
+            variable_byte_int.encode(&mut buffer),
+            Err(DataRepresentationError::VariableByteIntegerBufferOverflow)
+        );
+    }
+
     // #[test]
     // fn test_max_value() {
     //     let max_value = VariableByteInt::new(VariableByteInt::MAX_VALUE).unwrap();
-    //     let encoded = max_value.encode();
+    //     let encoded = max_value.to_bytes();
     //     let (decoded, length) = VariableByteInt::decode(&encoded).unwrap();
 
     //     assert_eq!(max_value, decoded);
     //     assert_eq!(length, 4); // should be the full length
     // }
 }
+
+#[cfg(test)]
+mod test_variable_byte_int_decoder {
+    use super::*;
+
+    #[test]
+    fn decodes_one_byte_at_a_time() {
+        let encoded = VariableByteInt::new(0x69420).unwrap().to_bytes();
+        let mut decoder = VariableByteIntDecoder::new();
+
+        assert_eq!(decoder.push(encoded[0]), Poll::Pending);
+        assert_eq!(decoder.push(encoded[1]), Poll::Pending);
+        assert_eq!(decoder.bytes_consumed(), 2);
+
+        let decoded = match decoder.push(encoded[2]) {
+            Poll::Ready(result) => result.unwrap(),
+            Poll::Pending => panic!("expected the final byte to complete the decode"),
+        };
+
+        assert_eq!(decoded.value(), 0x69420);
+        assert_eq!(decoded.length(), 3);
+    }
+
+    #[test]
+    fn decodes_maximum_value() {
+        let mut decoder = VariableByteIntDecoder::new();
+
+        for byte in [0xFF, 0xFF, 0xFF] {
+            assert_eq!(decoder.push(byte), Poll::Pending);
+        }
+
+        assert_eq!(
+            decoder.push(0x7F),
+            Poll::Ready(Ok(VariableByteInt::new(0x0FFF_FFFF).unwrap()))
+        );
+    }
+
+    #[test]
+    fn rejects_fifth_byte() {
+        let mut decoder = VariableByteIntDecoder::new();
+
+        for byte in [0xFF, 0xFF, 0xFF] {
+            assert_eq!(decoder.push(byte), Poll::Pending);
+        }
+
+        assert_eq!(
+            decoder.push(0xFF),
+            Poll::Ready(Err(DataRepresentationError::MalformedVariableByteInteger))
+        );
+    }
+
+    #[test]
+    fn resets_after_completion() {
+        let mut decoder = VariableByteIntDecoder::new();
+
+        assert!(matches!(decoder.push(0x80), Poll::Pending));
+        assert!(matches!(decoder.push(0x01), Poll::Ready(Ok(_))));
+        assert_eq!(decoder, VariableByteIntDecoder::new());
+
+        assert_eq!(
+            decoder.push(0x05),
+            Poll::Ready(Ok(VariableByteInt::new(5).unwrap()))
+        );
+    }
+}
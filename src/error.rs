@@ -1,6 +1,48 @@
+use crate::data_representation::DataRepresentationError;
+
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum MqttError {
     InvalidPacketType,
     InvalidQOSLevel,
+    InvalidDupFlag,
+    InvalidPacketIdentifier, // packet identifiers must be non-zero
     InvalidRetries,
+    UnsupportedProtocolVersion, // the CONNECT protocol level is not MQTT 5
+    InvalidKeepAlive,           // keep alives are at most 65535 seconds
+    InvalidClientId,            // strict client identifiers are 1 to 23 alphanumeric characters
+    InvalidReasonCode,          // the value is not a reason code defined for the packet
+    InvalidSessionPresent,      // only a successful CONNACK may report a session present
+    InvalidTopicName,           // topic names are non-empty and contain no wildcards
+    InvalidTopicFilter,         // wildcards must fill a whole level, and # must be the last
+
+    // encoding errors
+    BufferOverflow,
+
+    // decoding errors
+    MalformedPacket,
+    UnsupportedPacketType, // a valid packet type this crate cannot yet decode
+    IncompletePacket,      // more bytes are needed before the packet can be decoded
+    PacketTooLarge,        // the packet exceeds the maximum packet size
+
+    // properties errors
+    InvalidPropertyIdentifier,
+    MalformedProperties,
+    PropertiesCapacityExceeded,
+
+    // subscription errors
+    EmptySubscriptions, // SUBSCRIBE needs at least one topic filter, and SUBACK a reason code
+    SubscriptionsCapacityExceeded,
+
+    // errors from the underlying data representations
+    DataRepresentation(DataRepresentationError),
+}
+
+impl From<DataRepresentationError> for MqttError {
+    fn from(error: DataRepresentationError) -> Self {
+        match error {
+            DataRepresentationError::BufferOverflow => MqttError::BufferOverflow,
+            error => MqttError::DataRepresentation(error),
+        }
+    }
 }
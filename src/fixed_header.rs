@@ -1,27 +1,18 @@
-use crate::error::MqttError;
+//! MQTT communicates through the exchange of  MQTT control packets.
+//! An MQTT packet is comprised of 3 parts, in the same order:
+//! 1. The fixed header (all packets)
+//! 2. The variable header (some packets)
+//! 3. Payload (some packets)
 
-/// MQTT communicates through the exchange of  MQTT control packets.
-/// An MQTT packet is comprised of 3 parts, in the same order:
-/// /// 1. The fixed header (all packets)
-/// /// 2. The variable header (some packets)
-/// /// 3. Payload (some packets)
-
-const CONNECT_FLAGS: u8 = 0x00;
-const CONNACK_FLAGS: u8 = 0x00;
-const PUBACK_FLAGS: u8 = 0x00;
-const PUBREC_FLAGS: u8 = 0x00;
-const PUBREL_FLAGS: u8 = 0x02;
-const PUBCOMP_FLAGS: u8 = 0x00;
-const SUBSCRIBE_FLAGS: u8 = 0x02;
-const SUBACK_FLAGS: u8 = 0x00;
-const UNSUBSCRIBE_FLAGS: u8 = 0x02;
-const UNSUBACK_FLAGS: u8 = 0x00;
-const PINGREQ_FLAGS: u8 = 0x00;
-const PINGRESP_FLAGS: u8 = 0x00;
-const DISCONNECT_FLAGS: u8 = 0x00;
-const AUTH_FLAGS: u8 = 0x00;
+use crate::data_representation::{ByteWriter, VariableByteInt, VariableByteIntDecoder};
+use crate::error::MqttError;
+use core::fmt;
+use core::task::Poll;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u8)]
 pub enum QOS {
     ATMOSTONCE = 0,
@@ -30,6 +21,7 @@ pub enum QOS {
 }
 
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum FixedHeader {
     Standard {
         packet_type: ControlPacketType,
@@ -38,6 +30,7 @@ pub enum FixedHeader {
         packet_type: ControlPacketType,
         qos: QOS,
         dup: bool,
+        retain: bool,
     },
 }
 
@@ -46,79 +39,213 @@ impl FixedHeader {
     // known constant flags; for PUBLISH, applies 0x00 since the flags cannot be known at compile time.
     // Returns an error when the packet type is not valid for a given fixed header, e.g.
     // RESERVED is always an invalid header, and PUBLISH is invalid for a standard header.
-    // PUBLISH headers must be created with new_publish, since QOS and DUP flags are unknown at compile time.
-    pub fn new(packet_type: ControlPacketType) -> Result<Self, MqttError> {
+    // PUBLISH headers must be created with new_publish, since QOS, DUP and RETAIN flags are unknown at compile time.
+    pub const fn new(packet_type: ControlPacketType) -> Result<Self, MqttError> {
         match packet_type {
-            ControlPacketType::RESERVED => return Err(MqttError::InvalidPacketType),
-            ControlPacketType::PUBLISH => return Err(MqttError::InvalidPacketType),
+            ControlPacketType::RESERVED => Err(MqttError::InvalidPacketType),
+            ControlPacketType::PUBLISH => Err(MqttError::InvalidPacketType),
             _ => Ok(FixedHeader::Standard { packet_type }),
         }
     }
 
     // constructor for a PUBLISH Fixed Header.
-    // requires a QOS level (ATMOSTONCE, ATLEASTONCE, or EXACTLYONCE) and a DUP flag (true or false).
+    // requires a QOS level (ATMOSTONCE, ATLEASTONCE, or EXACTLYONCE), a DUP flag and a RETAIN flag.
     // DUP refers to whether this is a re-sending of the message, true means it's a DUP, false means it's the first time.
-    pub fn new_publish(qos: QOS, dup: bool) -> Result<Self, MqttError> {
+    // a QOS 0 message is never re-sent, so DUP must be false for it [MQTT-3.3.1-2].
+    // RETAIN asks the server to store the message and deliver it to future subscribers of the topic.
+    pub const fn new_publish(qos: QOS, dup: bool, retain: bool) -> Result<Self, MqttError> {
+        if dup && matches!(qos, QOS::ATMOSTONCE) {
+            return Err(MqttError::InvalidDupFlag);
+        }
+
         Ok(FixedHeader::Publish {
             packet_type: ControlPacketType::PUBLISH,
-            qos: qos,
-            dup: dup,
+            qos,
+            dup,
+            retain,
         })
     }
 
-    // encodes the FixedHeader as a byte array.
-    // the first 4 bits are the MQTT packet type, the next 4 bits are the flags.
-    // for a PUBLISH header, the last 3 bits are the QOS level and the DUP flag, and the final bit is always 0.
-    pub fn encode(&self) -> Result<[u8; 2], MqttError> {
-        let mut header: [u8; 2] = [0x00, 0x00];
-
+    // computes the first byte of the header: the packet type in the upper 4 bits, the flags in the lower 4.
+    // for a PUBLISH header, the flags are the DUP flag, the QOS level and the RETAIN flag.
+    // const, so headers known at compile time can be encoded at compile time.
+    pub const fn first_byte(&self) -> Result<u8, MqttError> {
         match self {
             FixedHeader::Standard { packet_type } => {
-                header[0] = (*packet_type as u8) << 4; // shift into first 4 bits
-                // encode flags
-                header[0] |= match *packet_type {
-                    ControlPacketType::CONNECT => CONNECT_FLAGS,
-                    ControlPacketType::CONNACK => CONNACK_FLAGS,
-                    ControlPacketType::PUBACK => PUBACK_FLAGS,
-                    ControlPacketType::PUBREC => PUBREC_FLAGS,
-                    ControlPacketType::PUBREL => PUBREL_FLAGS,
-                    ControlPacketType::PUBCOMP => PUBCOMP_FLAGS,
-                    ControlPacketType::SUBSCRIBE => SUBSCRIBE_FLAGS,
-                    ControlPacketType::SUBACK => SUBACK_FLAGS,
-                    ControlPacketType::UNSUBSCRIBE => UNSUBSCRIBE_FLAGS,
-                    ControlPacketType::UNSUBACK => UNSUBACK_FLAGS,
-                    ControlPacketType::PINGREQ => PINGREQ_FLAGS,
-                    ControlPacketType::PINGRESP => PINGRESP_FLAGS,
-                    ControlPacketType::DISCONNECT => DISCONNECT_FLAGS,
-                    ControlPacketType::AUTH => AUTH_FLAGS,
-                    _ => return Err(MqttError::InvalidPacketType),
+                let flags = match packet_type.expected_flags() {
+                    Some(flags) => flags,
+                    None => return Err(MqttError::InvalidPacketType),
                 };
+
+                Ok(((*packet_type as u8) << 4) | flags) // packet type in the first 4 bits
             }
             FixedHeader::Publish {
                 packet_type,
                 qos,
                 dup,
+                retain,
             } => {
                 // encode packet type
-                header[0] = (*packet_type as u8) << 4; // shift into first 4 bits
+                let mut byte = (*packet_type as u8) << 4; // shift into first 4 bits
 
                 // encode DUP flag (bit 3)
                 if *dup {
-                    header[0] |= 0x08; // set bit 3 to 1
+                    byte |= 0x08; // set bit 3 to 1
+                }
+
+                // encode RETAIN flag (bit 0)
+                if *retain {
+                    byte |= 0x01; // set bit 0 to 1
                 }
 
                 // encode QOS flags
-                header[0] |= (*qos as u8) << 1; // shift into the next 2 bits
+                Ok(byte | ((*qos as u8) << 1)) // shift into the next 2 bits
+            }
+        }
+    }
+
+    // encodes the FixedHeader, followed by the remaining length: the number of bytes in the
+    // variable header and payload, as a Variable Byte Integer of 1 to 4 bytes.
+    pub fn encode(&self, remaining_length: u32) -> Result<EncodedFixedHeader, MqttError> {
+        let remaining_length = VariableByteInt::new(remaining_length)?;
+
+        let mut bytes = [0; MAX_FIXED_HEADER_LEN];
+        let mut writer = ByteWriter::new(&mut bytes);
+        writer.put_u8(self.first_byte()?)?;
+        writer.put_vbi(remaining_length)?;
+        let len = writer.position();
+
+        Ok(EncodedFixedHeader { bytes, len })
+    }
+
+    // returns the number of bytes encode writes for the given remaining length, without encoding.
+    pub fn encoded_len(&self, remaining_length: u32) -> Result<usize, MqttError> {
+        Ok(1 + VariableByteInt::new(remaining_length)?.length())
+    }
+
+    // decodes a FixedHeader from the start of the buffer.
+    // returns the header, the remaining length (the number of bytes in the variable header and payload),
+    // and the number of bytes the fixed header itself occupied.
+    // returns IncompletePacket when the buffer ends before the remaining length does.
+    pub fn decode(buffer: &[u8]) -> Result<(Self, u32, usize), MqttError> {
+        let (&first_byte, rest) = buffer.split_first().ok_or(MqttError::IncompletePacket)?;
+
+        let packet_type = ControlPacketType::try_from(first_byte >> 4)?;
+        packet_type.validate_flags(first_byte & 0x0F)?;
+
+        let header = match packet_type {
+            ControlPacketType::PUBLISH => {
+                let qos = match (first_byte >> 1) & 0x03 {
+                    0 => QOS::ATMOSTONCE,
+                    1 => QOS::ATLEASTONCE,
+                    2 => QOS::EXACTLYONCE,
+                    _ => return Err(MqttError::InvalidQOSLevel),
+                };
+                let dup = first_byte & 0x08 != 0;
+                let retain = first_byte & 0x01 != 0;
+
+                FixedHeader::new_publish(qos, dup, retain)?
+            }
+            _ => FixedHeader::new(packet_type)?,
+        };
+
+        // the remaining length may be split across reads, so running out of bytes is not malformed
+        let mut decoder = VariableByteIntDecoder::new();
+        for &byte in rest {
+            if let Poll::Ready(remaining_length) = decoder.push(byte) {
+                let remaining_length = remaining_length?;
+                return Ok((
+                    header,
+                    remaining_length.value(),
+                    1 + remaining_length.length(),
+                ));
             }
         }
 
-        header[1] = 0x00; // placeholder for "remaining length" field
+        Err(MqttError::IncompletePacket)
+    }
+}
+
+// the first byte, plus a remaining length of up to 4 bytes
+const MAX_FIXED_HEADER_LEN: usize = 5;
+
+/// An encoded fixed header, between 2 and 5 bytes long
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncodedFixedHeader {
+    bytes: [u8; MAX_FIXED_HEADER_LEN],
+    len: usize,
+}
+
+impl EncodedFixedHeader {
+    /// Returns the encoded bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bytes.get(..self.len).unwrap_or_default()
+    }
+}
+
+// packets with neither a variable header nor a payload are fully known at compile time,
+// so firmware can embed them pre-encoded (e.g. in flash) at zero runtime cost.
+
+/// A pre-encoded PINGREQ packet
+pub const PINGREQ_PACKET: [u8; 2] = empty_packet(ControlPacketType::PINGREQ);
+
+/// A pre-encoded PINGRESP packet
+pub const PINGRESP_PACKET: [u8; 2] = empty_packet(ControlPacketType::PINGRESP);
+
+/// A pre-encoded DISCONNECT packet for a normal disconnection; with a reason code
+/// of 0x00 and no properties, the variable header may be omitted entirely
+pub const DISCONNECT_PACKET: [u8; 2] = empty_packet(ControlPacketType::DISCONNECT);
+
+// evaluated at compile time, so an invalid header fails the build rather than the firmware
+#[allow(clippy::panic)]
+const fn empty_packet(packet_type: ControlPacketType) -> [u8; 2] {
+    let first_byte = match FixedHeader::new(packet_type) {
+        Ok(header) => header.first_byte(),
+        Err(error) => Err(error),
+    };
+
+    match first_byte {
+        Ok(first_byte) => [first_byte, 0x00], // remaining length of 0
+        Err(_) => panic!("packet type has no body-less encoding"),
+    }
+}
+
+/// Formatted as the packet type, followed by the flags for a PUBLISH header
+impl fmt::Display for FixedHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FixedHeader::Standard { packet_type } => write!(f, "{packet_type}"),
+            FixedHeader::Publish {
+                packet_type,
+                qos,
+                dup,
+                retain,
+            } => write!(
+                f,
+                "{packet_type} qos={} dup={dup} retain={retain}",
+                *qos as u8
+            ),
+        }
+    }
+}
 
-        Ok(header)
+// only headers accepted by the constructors are generated, so every value encodes
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for FixedHeader {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        if u.arbitrary()? {
+            FixedHeader::new_publish(u.arbitrary()?, u.arbitrary()?, u.arbitrary()?)
+        } else {
+            FixedHeader::new(u.arbitrary()?)
+        }
+        .map_err(|_| arbitrary::Error::IncorrectFormat)
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u8)]
 pub enum ControlPacketType {
     RESERVED = 0,     // Reserved for future use
@@ -139,6 +266,70 @@ pub enum ControlPacketType {
     AUTH = 15,        // Client <-> Server, authentication exchange
 }
 
+impl ControlPacketType {
+    // the reserved flags (lower 4 bits of the first byte) every packet of this type must carry.
+    // PUBLISH has no fixed flags, since they carry its DUP, QOS and RETAIN settings; RESERVED is never sent.
+    pub const fn expected_flags(&self) -> Option<u8> {
+        match self {
+            ControlPacketType::RESERVED | ControlPacketType::PUBLISH => None,
+            // PUBREL, SUBSCRIBE and UNSUBSCRIBE are the only packets with a non-zero reserved value
+            ControlPacketType::PUBREL
+            | ControlPacketType::SUBSCRIBE
+            | ControlPacketType::UNSUBSCRIBE => Some(0x02),
+            _ => Some(0x00),
+        }
+    }
+
+    // checks the flags from a received first byte against the reserved flags for this packet type.
+    // any deviation makes the packet malformed [MQTT-2.1.3-1]. PUBLISH flags are always accepted here,
+    // and are validated when the header is built from them.
+    pub const fn validate_flags(&self, flags: u8) -> Result<(), MqttError> {
+        match self {
+            ControlPacketType::RESERVED => Err(MqttError::InvalidPacketType),
+            ControlPacketType::PUBLISH => Ok(()),
+            _ => match self.expected_flags() {
+                Some(expected) if expected == flags => Ok(()),
+                _ => Err(MqttError::MalformedPacket),
+            },
+        }
+    }
+}
+
+// maps the packet type value (the upper 4 bits of the first byte) back to a ControlPacketType.
+// RESERVED is rejected along with values above 15, since it never appears on the wire.
+impl TryFrom<u8> for ControlPacketType {
+    type Error = MqttError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(ControlPacketType::CONNECT),
+            2 => Ok(ControlPacketType::CONNACK),
+            3 => Ok(ControlPacketType::PUBLISH),
+            4 => Ok(ControlPacketType::PUBACK),
+            5 => Ok(ControlPacketType::PUBREC),
+            6 => Ok(ControlPacketType::PUBREL),
+            7 => Ok(ControlPacketType::PUBCOMP),
+            8 => Ok(ControlPacketType::SUBSCRIBE),
+            9 => Ok(ControlPacketType::SUBACK),
+            10 => Ok(ControlPacketType::UNSUBSCRIBE),
+            11 => Ok(ControlPacketType::UNSUBACK),
+            12 => Ok(ControlPacketType::PINGREQ),
+            13 => Ok(ControlPacketType::PINGRESP),
+            14 => Ok(ControlPacketType::DISCONNECT),
+            15 => Ok(ControlPacketType::AUTH),
+            _ => Err(MqttError::InvalidPacketType),
+        }
+    }
+}
+
+/// Formatted as the name used by the spec, e.g. `PUBACK`
+impl fmt::Display for ControlPacketType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // the variants are named after the spec, so their debug form is the spec's name
+        write!(f, "{self:?}")
+    }
+}
+
 #[cfg(test)]
 mod test_fixed_header_new {
     use super::*;
@@ -315,6 +506,12 @@ mod test_fixed_header_new {
         assert!(header.is_err());
     }
 
+    #[test]
+    fn test_new_publish_dup_at_qos_0() {
+        let header = FixedHeader::new_publish(QOS::ATMOSTONCE, true, false);
+        assert_eq!(header, Err(MqttError::InvalidDupFlag));
+    }
+
     #[test]
     fn test_new_invalid_packet_type() {
         let header = FixedHeader::new(ControlPacketType::RESERVED);
@@ -335,138 +532,140 @@ mod test_fixed_header_encode {
     #[test]
     fn test_encode_connect() {
         let header = FixedHeader::new(ControlPacketType::CONNECT).unwrap();
-        let encoded = header.encode().unwrap();
+        let encoded = header.encode(0).unwrap();
 
-        assert_eq!(encoded, [0b00010000, 0x00])
+        assert_eq!(encoded.as_bytes(), [0b00010000, 0x00])
     }
 
     #[test]
     fn test_encode_connack() {
         let header = FixedHeader::new(ControlPacketType::CONNACK).unwrap();
-        let encoded = header.encode().unwrap();
+        let encoded = header.encode(0).unwrap();
 
-        assert_eq!(encoded, [0b00100000, 0x00])
+        assert_eq!(encoded.as_bytes(), [0b00100000, 0x00])
     }
 
     #[test]
     fn test_encode_publish() {
         let headers = [
-            FixedHeader::new_publish(QOS::ATMOSTONCE, false).unwrap(),
-            FixedHeader::new_publish(QOS::ATLEASTONCE, false).unwrap(),
-            FixedHeader::new_publish(QOS::EXACTLYONCE, false).unwrap(),
-            FixedHeader::new_publish(QOS::ATMOSTONCE, true).unwrap(),
-            FixedHeader::new_publish(QOS::ATLEASTONCE, true).unwrap(),
-            FixedHeader::new_publish(QOS::EXACTLYONCE, true).unwrap(),
+            FixedHeader::new_publish(QOS::ATMOSTONCE, false, false).unwrap(),
+            FixedHeader::new_publish(QOS::ATLEASTONCE, false, false).unwrap(),
+            FixedHeader::new_publish(QOS::EXACTLYONCE, false, false).unwrap(),
+            FixedHeader::new_publish(QOS::ATLEASTONCE, true, false).unwrap(),
+            FixedHeader::new_publish(QOS::EXACTLYONCE, true, false).unwrap(),
+            FixedHeader::new_publish(QOS::ATMOSTONCE, false, true).unwrap(),
+            FixedHeader::new_publish(QOS::EXACTLYONCE, true, true).unwrap(),
         ];
-        let expected_headers: [[u8; 2]; 6] = [
+        let expected_headers: [[u8; 2]; 7] = [
             [0b00110000, 0x00],
             [0b00110010, 0x00],
             [0b00110100, 0x00],
-            [0b00111000, 0x00],
             [0b00111010, 0x00],
             [0b00111100, 0x00],
+            [0b00110001, 0x00],
+            [0b00111101, 0x00],
         ];
 
         for (i, header) in headers.iter().enumerate() {
-            let encoded = header.encode().unwrap();
-            assert_eq!(encoded, expected_headers[i]);
+            let encoded = header.encode(0).unwrap();
+            assert_eq!(encoded.as_bytes(), expected_headers[i]);
         }
     }
 
     #[test]
     fn test_encode_puback() {
         let header = FixedHeader::new(ControlPacketType::PUBACK).unwrap();
-        let encoded = header.encode().unwrap();
+        let encoded = header.encode(0).unwrap();
 
-        assert_eq!(encoded, [0b01000000, 0x00])
+        assert_eq!(encoded.as_bytes(), [0b01000000, 0x00])
     }
 
     #[test]
     fn test_encode_pubrec() {
         let header = FixedHeader::new(ControlPacketType::PUBREC).unwrap();
-        let encoded = header.encode().unwrap();
+        let encoded = header.encode(0).unwrap();
 
-        assert_eq!(encoded, [0b01010000, 0x00])
+        assert_eq!(encoded.as_bytes(), [0b01010000, 0x00])
     }
 
     #[test]
     fn test_encode_pubrel() {
         let header = FixedHeader::new(ControlPacketType::PUBREL).unwrap();
-        let encoded = header.encode().unwrap();
+        let encoded = header.encode(0).unwrap();
 
-        assert_eq!(encoded, [0b01100010, 0x00])
+        assert_eq!(encoded.as_bytes(), [0b01100010, 0x00])
     }
 
     #[test]
     fn test_encode_pubcomp() {
         let header = FixedHeader::new(ControlPacketType::PUBCOMP).unwrap();
-        let encoded = header.encode().unwrap();
+        let encoded = header.encode(0).unwrap();
 
-        assert_eq!(encoded, [0b01110000, 0x00])
+        assert_eq!(encoded.as_bytes(), [0b01110000, 0x00])
     }
 
     #[test]
     fn test_encode_subscribe() {
         let header = FixedHeader::new(ControlPacketType::SUBSCRIBE).unwrap();
-        let encoded = header.encode().unwrap();
+        let encoded = header.encode(0).unwrap();
 
-        assert_eq!(encoded, [0b10000010, 0x00])
+        assert_eq!(encoded.as_bytes(), [0b10000010, 0x00])
     }
 
     #[test]
     fn test_encode_suback() {
         let header = FixedHeader::new(ControlPacketType::SUBACK).unwrap();
-        let encoded = header.encode().unwrap();
+        let encoded = header.encode(0).unwrap();
 
-        assert_eq!(encoded, [0b10010000, 0x00])
+        assert_eq!(encoded.as_bytes(), [0b10010000, 0x00])
     }
 
     #[test]
     fn test_encode_unsubscribe() {
         let header = FixedHeader::new(ControlPacketType::UNSUBSCRIBE).unwrap();
-        let encoded = header.encode().unwrap();
+        let encoded = header.encode(0).unwrap();
 
-        assert_eq!(encoded, [0b10100010, 0x00])
+        assert_eq!(encoded.as_bytes(), [0b10100010, 0x00])
     }
 
     #[test]
     fn test_encode_unsuback() {
         let header = FixedHeader::new(ControlPacketType::UNSUBACK).unwrap();
-        let encoded = header.encode().unwrap();
+        let encoded = header.encode(0).unwrap();
 
-        assert_eq!(encoded, [0b10110000, 0x00])
+        assert_eq!(encoded.as_bytes(), [0b10110000, 0x00])
     }
 
     #[test]
     fn test_encode_pingreq() {
         let header = FixedHeader::new(ControlPacketType::PINGREQ).unwrap();
-        let encoded = header.encode().unwrap();
+        let encoded = header.encode(0).unwrap();
 
-        assert_eq!(encoded, [0b11000000, 0x00])
+        assert_eq!(encoded.as_bytes(), [0b11000000, 0x00])
     }
 
     #[test]
     fn test_encode_pingresp() {
         let header = FixedHeader::new(ControlPacketType::PINGRESP).unwrap();
-        let encoded = header.encode().unwrap();
+        let encoded = header.encode(0).unwrap();
 
-        assert_eq!(encoded, [0b11010000, 0x00])
+        assert_eq!(encoded.as_bytes(), [0b11010000, 0x00])
     }
 
     #[test]
     fn test_encode_disconnect() {
         let header = FixedHeader::new(ControlPacketType::DISCONNECT).unwrap();
-        let encoded = header.encode().unwrap();
+        let encoded = header.encode(0).unwrap();
 
-        assert_eq!(encoded, [0b11100000, 0x00])
+        assert_eq!(encoded.as_bytes(), [0b11100000, 0x00])
     }
 
     #[test]
     fn test_encode_auth() {
         let header = FixedHeader::new(ControlPacketType::AUTH).unwrap();
-        let encoded = header.encode().unwrap();
+        let encoded = header.encode(0).unwrap();
 
-        assert_eq!(encoded, [0b11110000, 0x00])
+        assert_eq!(encoded.as_bytes(), [0b11110000, 0x00])
     }
 
     #[test]
@@ -474,7 +673,257 @@ mod test_fixed_header_encode {
         let header = FixedHeader::Standard {
             packet_type: ControlPacketType::RESERVED,
         };
-        let encoded = header.encode();
+        let encoded = header.encode(0);
         assert!(encoded.is_err());
     }
+
+    #[test]
+    fn test_encode_remaining_length() {
+        let header = FixedHeader::new(ControlPacketType::CONNECT).unwrap();
+
+        let lengths: [(u32, &[u8]); 4] = [
+            (127, &[0x10, 0x7F]),
+            (128, &[0x10, 0x80, 0x01]),
+            (16_384, &[0x10, 0x80, 0x80, 0x01]),
+            (268_435_455, &[0x10, 0xFF, 0xFF, 0xFF, 0x7F]),
+        ];
+
+        for (remaining_length, expected) in lengths {
+            let encoded = header.encode(remaining_length).unwrap();
+            assert_eq!(encoded.as_bytes(), expected);
+        }
+    }
+
+    #[test]
+    fn test_encode_remaining_length_out_of_range() {
+        let header = FixedHeader::new(ControlPacketType::CONNECT).unwrap();
+        let encoded = header.encode(268_435_456);
+        assert!(encoded.is_err());
+    }
+
+    #[test]
+    fn test_encoded_len_matches_encode() {
+        let header = FixedHeader::new(ControlPacketType::SUBSCRIBE).unwrap();
+
+        for remaining_length in [0, 127, 128, 16_383, 16_384, 2_097_152, 268_435_455] {
+            assert_eq!(
+                header.encoded_len(remaining_length),
+                Ok(header.encode(remaining_length).unwrap().as_bytes().len())
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_fixed_header_decode {
+    use super::*;
+    use crate::data_representation::DataRepresentationError;
+
+    #[test]
+    fn test_decode_standard() {
+        let buffer = [0b10000010, 0x05, 0xFF]; // SUBSCRIBE, remaining length 5
+
+        let (header, remaining_length, consumed) = FixedHeader::decode(&buffer).unwrap();
+
+        assert_eq!(
+            header,
+            FixedHeader::new(ControlPacketType::SUBSCRIBE).unwrap()
+        );
+        assert_eq!(remaining_length, 5);
+        assert_eq!(consumed, 2);
+    }
+
+    #[test]
+    fn test_decode_publish() {
+        let buffer = [0b00111101, 0x80, 0x01]; // PUBLISH, DUP, QoS 2, RETAIN, remaining length 128
+
+        let (header, remaining_length, consumed) = FixedHeader::decode(&buffer).unwrap();
+
+        assert_eq!(
+            header,
+            FixedHeader::new_publish(QOS::EXACTLYONCE, true, true).unwrap()
+        );
+        assert_eq!(remaining_length, 128);
+        assert_eq!(consumed, 3);
+    }
+
+    #[test]
+    fn test_decode_reversibility() {
+        let header = FixedHeader::new(ControlPacketType::PUBREL).unwrap();
+        let encoded = header.encode(321).unwrap();
+
+        assert_eq!(
+            FixedHeader::decode(encoded.as_bytes()),
+            Ok((header, 321, encoded.as_bytes().len()))
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid_flags() {
+        let buffer = [0b10000000, 0x00]; // SUBSCRIBE requires flags of 0b0010
+        assert_eq!(
+            FixedHeader::decode(&buffer),
+            Err(MqttError::MalformedPacket)
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid_qos() {
+        let buffer = [0b00110110, 0x00]; // PUBLISH with both QoS bits set
+        assert_eq!(
+            FixedHeader::decode(&buffer),
+            Err(MqttError::InvalidQOSLevel)
+        );
+    }
+
+    #[test]
+    fn test_decode_publish_dup_at_qos_0() {
+        let buffer = [0b00111000, 0x00]; // PUBLISH, DUP, QoS 0
+        assert_eq!(FixedHeader::decode(&buffer), Err(MqttError::InvalidDupFlag));
+    }
+
+    #[test]
+    fn test_decode_reserved_packet_type() {
+        let buffer = [0x00, 0x00];
+        assert_eq!(
+            FixedHeader::decode(&buffer),
+            Err(MqttError::InvalidPacketType)
+        );
+    }
+
+    #[test]
+    fn test_decode_incomplete() {
+        assert_eq!(FixedHeader::decode(&[]), Err(MqttError::IncompletePacket));
+        assert_eq!(
+            FixedHeader::decode(&[0b11000000, 0x80]),
+            Err(MqttError::IncompletePacket)
+        );
+    }
+
+    #[test]
+    fn test_decode_malformed_remaining_length() {
+        let buffer = [0b11000000, 0xFF, 0xFF, 0xFF, 0xFF];
+        assert_eq!(
+            FixedHeader::decode(&buffer),
+            Err(MqttError::DataRepresentation(
+                DataRepresentationError::MalformedVariableByteInteger
+            ))
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_control_packet_type {
+    use super::*;
+
+    #[test]
+    fn test_try_from_round_trips_every_packet_type() {
+        for value in 1..=15u8 {
+            let packet_type = ControlPacketType::try_from(value).unwrap();
+            assert_eq!(packet_type as u8, value);
+        }
+    }
+
+    #[test]
+    fn test_try_from_rejects_reserved_and_out_of_range() {
+        assert_eq!(
+            ControlPacketType::try_from(0),
+            Err(MqttError::InvalidPacketType)
+        );
+        assert_eq!(
+            ControlPacketType::try_from(16),
+            Err(MqttError::InvalidPacketType)
+        );
+    }
+
+    #[test]
+    fn test_expected_flags() {
+        assert_eq!(ControlPacketType::CONNECT.expected_flags(), Some(0x00));
+        assert_eq!(ControlPacketType::PUBREL.expected_flags(), Some(0x02));
+        assert_eq!(ControlPacketType::SUBSCRIBE.expected_flags(), Some(0x02));
+        assert_eq!(ControlPacketType::UNSUBSCRIBE.expected_flags(), Some(0x02));
+        assert_eq!(ControlPacketType::PUBLISH.expected_flags(), None);
+        assert_eq!(ControlPacketType::RESERVED.expected_flags(), None);
+    }
+
+    #[test]
+    fn test_validate_flags() {
+        assert_eq!(ControlPacketType::SUBSCRIBE.validate_flags(0x02), Ok(()));
+        assert_eq!(
+            ControlPacketType::SUBSCRIBE.validate_flags(0x00),
+            Err(MqttError::MalformedPacket)
+        );
+        assert_eq!(
+            ControlPacketType::PINGREQ.validate_flags(0x01),
+            Err(MqttError::MalformedPacket)
+        );
+        assert_eq!(ControlPacketType::PUBLISH.validate_flags(0x0F), Ok(()));
+        assert_eq!(
+            ControlPacketType::RESERVED.validate_flags(0x00),
+            Err(MqttError::InvalidPacketType)
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_fixed_header_display {
+    use super::*;
+
+    #[test]
+    fn test_display_standard() {
+        let header = FixedHeader::new(ControlPacketType::PUBREL).unwrap();
+        assert_eq!(format!("{header}"), "PUBREL");
+    }
+
+    #[test]
+    fn test_display_publish() {
+        let header = FixedHeader::new_publish(QOS::ATLEASTONCE, true, false).unwrap();
+        assert_eq!(format!("{header}"), "PUBLISH qos=1 dup=true retain=false");
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test_fixed_header_serde {
+    use super::*;
+    use serde_test::{Token, assert_tokens};
+
+    #[test]
+    fn serializes_by_name() {
+        assert_tokens(
+            &QOS::ATLEASTONCE,
+            &[Token::UnitVariant {
+                name: "QOS",
+                variant: "ATLEASTONCE",
+            }],
+        );
+        assert_tokens(
+            &ControlPacketType::SUBSCRIBE,
+            &[Token::UnitVariant {
+                name: "ControlPacketType",
+                variant: "SUBSCRIBE",
+            }],
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_static_packets {
+    use super::*;
+
+    #[test]
+    fn match_runtime_encoding() {
+        let packets = [
+            (ControlPacketType::PINGREQ, PINGREQ_PACKET),
+            (ControlPacketType::PINGRESP, PINGRESP_PACKET),
+            (ControlPacketType::DISCONNECT, DISCONNECT_PACKET),
+        ];
+
+        for (packet_type, packet) in packets {
+            let encoded = FixedHeader::new(packet_type).unwrap().encode(0).unwrap();
+
+            assert_eq!(encoded.as_bytes(), packet);
+        }
+
+        assert_eq!(PINGREQ_PACKET, [0xC0, 0x00]);
+    }
 }
@@ -1,7 +1,23 @@
-#[cfg_attr(not(test), no_std)]
-#[cfg(test)]
-extern crate std;
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+// malformed input must surface as an error, never a panic
+#![cfg_attr(
+    not(test),
+    deny(
+        clippy::unwrap_used,
+        clippy::expect_used,
+        clippy::panic,
+        clippy::indexing_slicing
+    )
+)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
-mod data_representation; // data representations per the spec
+pub mod data_representation; // data representations per the spec
 pub mod error;
 pub mod fixed_header;
+pub mod packet; // complete control packets, from fixed header to payload
+pub mod properties;
+
+#[cfg(test)]
+mod no_panic; // exercises every decoder with malformed input
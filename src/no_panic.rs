@@ -0,0 +1,191 @@
+// no-panic harness: every decoder is fed exhaustive short inputs and a
+// deterministic stream of pseudo-random longer ones. Decoders may reject
+// the input, but they must never panic on it.
+
+use crate::data_representation::{
+    BinaryData, BinaryDataRef, ByteReader, FixedStr, FourByteInt, MqttDecode, TwoByteInt,
+    Utf8StrRef, Utf8String, Utf8StringPair, VariableByteInt,
+};
+use crate::fixed_header::FixedHeader;
+use crate::packet::{
+    ConnAck, Connect, Disconnect, Packet, PacketDecoder, PingReq, PingResp, Publish, SubAck,
+    Subscribe,
+};
+use crate::properties::{Properties, Property};
+
+const EXHAUSTIVE_LEN: usize = 2; // every input up to this length is tried
+const RANDOM_INPUTS: usize = 10_000;
+const RANDOM_MAX_LEN: usize = 64;
+
+/// Small xorshift generator; deterministic so failures are reproducible
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+/// Calls `f` with every byte sequence up to `EXHAUSTIVE_LEN` bytes, followed by
+/// `RANDOM_INPUTS` pseudo-random sequences of up to `RANDOM_MAX_LEN` bytes
+pub(crate) fn for_each_input(mut f: impl FnMut(&[u8])) {
+    f(&[]);
+    for a in 0..=u8::MAX {
+        f(&[a]);
+        for b in 0..=u8::MAX {
+            f(&[a, b]);
+        }
+    }
+
+    let mut rng = XorShift(0x6d69_6467_655f_6e70);
+    let mut buffer = [0u8; RANDOM_MAX_LEN];
+    for _ in 0..RANDOM_INPUTS {
+        let len = EXHAUSTIVE_LEN + (rng.next() as usize % (RANDOM_MAX_LEN - EXHAUSTIVE_LEN));
+        for byte in buffer.iter_mut().take(len) {
+            *byte = rng.next() as u8;
+        }
+        f(&buffer[..len]);
+    }
+}
+
+#[test]
+fn fixed_width_int_decode_never_panics() {
+    for_each_input(|input| {
+        let _ = TwoByteInt::decode(input);
+        let _ = FourByteInt::decode(input);
+    });
+}
+
+// structured round trips: arbitrary (valid) values must encode to their encoded_len, then decode unchanged
+#[cfg(feature = "arbitrary")]
+#[test]
+fn arbitrary_values_round_trip() {
+    use crate::data_representation::MqttEncode;
+    use arbitrary::{Arbitrary, Unstructured};
+
+    for_each_input(|input| {
+        let mut u = Unstructured::new(input);
+        let mut buffer = [0u8; RANDOM_MAX_LEN + 2];
+
+        if let Ok(vbi) = VariableByteInt::arbitrary(&mut u) {
+            let encoded_len = vbi.encode(&mut buffer).unwrap();
+            assert_eq!(vbi.encoded_len(), encoded_len);
+            assert_eq!(VariableByteInt::decode(&buffer), Ok((vbi, encoded_len)));
+        }
+
+        if let Ok(utf8_string) = Utf8String::<{ RANDOM_MAX_LEN }>::arbitrary(&mut u) {
+            let encoded_len = utf8_string.encode(&mut buffer).unwrap();
+            assert_eq!(utf8_string.encoded_len(), encoded_len);
+            assert_eq!(Utf8String::decode(&buffer), Ok((utf8_string, encoded_len)));
+        }
+
+        if let (Ok(header), Ok(remaining_length)) = (
+            FixedHeader::arbitrary(&mut u),
+            VariableByteInt::arbitrary(&mut u),
+        ) {
+            let encoded = header.encode(remaining_length.value()).unwrap();
+            assert_eq!(
+                FixedHeader::decode(encoded.as_bytes()),
+                Ok((header, remaining_length.value(), encoded.as_bytes().len()))
+            );
+        }
+    });
+}
+
+#[test]
+fn fixed_header_decode_never_panics() {
+    for_each_input(|input| {
+        let _ = FixedHeader::decode(input);
+    });
+}
+
+#[test]
+fn variable_byte_int_decode_never_panics() {
+    for_each_input(|input| {
+        let _ = VariableByteInt::decode(input);
+    });
+}
+
+#[test]
+fn utf8_string_decode_never_panics() {
+    for_each_input(|input| {
+        let _ = Utf8String::<8>::decode(input);
+        let _ = Utf8String::<{ RANDOM_MAX_LEN }>::decode(input);
+        let _ = Utf8StrRef::decode(input);
+        let _ = FixedStr::<8>::decode(input);
+    });
+}
+
+#[test]
+fn binary_data_decode_never_panics() {
+    for_each_input(|input| {
+        let _ = BinaryData::<8>::decode(input);
+        let _ = BinaryData::<{ RANDOM_MAX_LEN }>::decode(input);
+        let _ = BinaryDataRef::decode(input);
+    });
+}
+
+#[test]
+fn byte_reader_never_panics() {
+    for_each_input(|input| {
+        let mut reader = ByteReader::new(input);
+        while !reader.is_empty() {
+            let _ = reader.read_vbi();
+            let _ = reader.read_utf8_str();
+            let _ = reader.read_u32();
+            let _ = reader.read_u16();
+            let _ = reader.read_bytes(3);
+            if reader.read_u8().is_err() {
+                break;
+            }
+        }
+    });
+}
+
+#[test]
+fn utf8_string_pair_decode_never_panics() {
+    for_each_input(|input| {
+        let _ = Utf8StringPair::<8>::decode(input);
+    });
+}
+
+#[test]
+fn properties_decode_never_panics() {
+    for_each_input(|input| {
+        let _ = Property::decode(input);
+        let _ = Properties::<4>::decode(input);
+    });
+}
+
+#[test]
+fn packet_decode_never_panics() {
+    for_each_input(|input| {
+        let _ = Packet::<4>::decode(input);
+        let _ = Connect::<4>::decode(input);
+        let _ = ConnAck::<4>::decode(input);
+        let _ = Publish::<4>::decode(input);
+        let _ = Subscribe::<4>::decode(input);
+        let _ = SubAck::<4>::decode(input);
+        let _ = Disconnect::<4>::decode(input);
+        let _ = PingReq::decode(input);
+        let _ = PingResp::decode(input);
+    });
+}
+
+#[test]
+fn packet_decoder_never_panics() {
+    let mut decoder = PacketDecoder::<32>::new();
+
+    for_each_input(|input| {
+        // split each input in two, so packets also straddle feeds
+        let (first, second) = input.split_at(input.len() / 2);
+        for mut chunk in [first, second] {
+            while let Ok((consumed @ 1.., _)) = decoder.feed::<4>(chunk) {
+                chunk = &chunk[consumed..];
+            }
+        }
+    });
+}
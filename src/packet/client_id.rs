@@ -0,0 +1,201 @@
+use crate::data_representation::Utf8StrRef;
+use crate::error::MqttError;
+use core::fmt;
+
+// the longest client identifier, and the characters in it, every server must accept [MQTT-3.1.3-5]
+const MAX_LEN: usize = 23;
+#[cfg(feature = "rand")]
+const ALPHABET: &[u8; 62] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// The Client Identifier sent in CONNECT, identifying the session to the server.
+///
+/// Every server accepts identifiers of 1 to 23 alphanumeric characters; `ClientId::new`
+/// enforces that. Many servers accept more, so `ClientId::relaxed` allows any valid
+/// UTF-8 string of up to 65535 bytes. The empty identifier asks the server to assign one,
+/// which it returns in CONNACK.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ClientId<'a>(&'a str);
+
+impl<'a> ClientId<'a> {
+    /// The longest identifier every server is required to accept
+    pub const MAX_LEN: usize = MAX_LEN;
+
+    /// Creates an identifier of 1 to 23 characters from [0-9a-zA-Z],
+    /// which every server is required to accept
+    pub const fn new(client_id: &'a str) -> Result<Self, MqttError> {
+        let bytes = client_id.as_bytes();
+        if bytes.is_empty() || bytes.len() > Self::MAX_LEN {
+            return Err(MqttError::InvalidClientId);
+        }
+
+        let mut rest = bytes;
+        while let [byte, tail @ ..] = rest {
+            if !byte.is_ascii_alphanumeric() {
+                return Err(MqttError::InvalidClientId);
+            }
+            rest = tail;
+        }
+
+        Ok(Self(client_id))
+    }
+
+    /// Creates an identifier from any valid MQTT UTF-8 string of up to 65535 bytes.
+    /// The server may reject identifiers `ClientId::new` would not accept.
+    pub fn relaxed(client_id: &'a str) -> Result<Self, MqttError> {
+        Ok(Self(Utf8StrRef::new(client_id)?.as_str()))
+    }
+
+    // wraps an identifier the reader has already validated, possibly leniently
+    pub(crate) const fn from_validated(client_id: &'a str) -> Self {
+        Self(client_id)
+    }
+
+    /// Creates the empty identifier, asking the server to assign one
+    pub const fn server_assigned() -> Self {
+        Self("")
+    }
+
+    /// Generates a random identifier of 23 alphanumeric characters, written to `buffer`
+    #[cfg(feature = "rand")]
+    pub fn random<R: rand_core::RngCore + ?Sized>(
+        rng: &mut R,
+        buffer: &'a mut [u8; MAX_LEN],
+    ) -> Self {
+        for byte in buffer.iter_mut() {
+            // scales the random value onto the alphabet; the bias this leaves is
+            // negligible next to the 2^32 range
+            let index = (u64::from(rng.next_u32()) * ALPHABET.len() as u64) >> 32;
+            *byte = ALPHABET.get(index as usize).copied().unwrap_or(b'0');
+        }
+
+        Self(core::str::from_utf8(buffer).unwrap_or_default())
+    }
+
+    /// Returns the identifier as a string slice
+    pub const fn as_str(&self) -> &'a str {
+        self.0
+    }
+
+    /// Returns the length of the identifier in bytes
+    pub const fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether the identifier is empty, asking the server to assign one
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns whether every server is required to accept the identifier
+    pub fn is_universally_accepted(&self) -> bool {
+        Self::new(self.0).is_ok()
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ClientId<'a> {
+    type Error = MqttError;
+
+    /// Equivalent to `ClientId::new`
+    fn try_from(client_id: &'a str) -> Result<Self, Self::Error> {
+        Self::new(client_id)
+    }
+}
+
+// only identifiers `ClientId::relaxed` accepts are generated, so every value encodes
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ClientId<'a> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Self::relaxed(u.arbitrary()?).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+impl fmt::Display for ClientId<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+#[cfg(test)]
+mod test_client_id {
+    use super::*;
+    use crate::data_representation::DataRepresentationError;
+
+    #[test]
+    fn test_new_accepts_alphanumeric() {
+        for client_id in ["c", "client1", "ABCDEFGHIJKLMNOPQRSTUVW"] {
+            assert_eq!(
+                ClientId::new(client_id).map(|id| id.as_str()),
+                Ok(client_id)
+            );
+        }
+    }
+
+    #[test]
+    fn test_new_rejects_invalid() {
+        for client_id in [
+            "",
+            "client-1",
+            "client 1",
+            "ABCDEFGHIJKLMNOPQRSTUVWX",
+            "clïent",
+        ] {
+            assert_eq!(ClientId::new(client_id), Err(MqttError::InvalidClientId));
+        }
+    }
+
+    #[test]
+    fn test_relaxed() {
+        let client_id = ClientId::relaxed("sensors/kitchen-thermometer").unwrap();
+        assert!(!client_id.is_universally_accepted());
+
+        assert_eq!(
+            ClientId::relaxed("nul\0"),
+            Err(MqttError::DataRepresentation(
+                DataRepresentationError::NullTerminatorInString
+            ))
+        );
+    }
+
+    #[test]
+    fn test_server_assigned() {
+        let client_id = ClientId::server_assigned();
+
+        assert!(client_id.is_empty());
+        assert_eq!(ClientId::relaxed(""), Ok(client_id));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_random() {
+        // an xorshift generator, to keep the test deterministic
+        struct XorShift(u32);
+
+        impl rand_core::RngCore for XorShift {
+            fn next_u32(&mut self) -> u32 {
+                self.0 ^= self.0 << 13;
+                self.0 ^= self.0 >> 17;
+                self.0 ^= self.0 << 5;
+                self.0
+            }
+
+            fn next_u64(&mut self) -> u64 {
+                rand_core::impls::next_u64_via_u32(self)
+            }
+
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                rand_core::impls::fill_bytes_via_next(self, dest)
+            }
+        }
+
+        let mut rng = XorShift(1);
+        let mut first = [0; ClientId::MAX_LEN];
+        let mut second = [0; ClientId::MAX_LEN];
+        let first = ClientId::random(&mut rng, &mut first);
+        let second = ClientId::random(&mut rng, &mut second);
+
+        assert!(first.is_universally_accepted());
+        assert_eq!(first.len(), ClientId::MAX_LEN);
+        assert_ne!(first, second);
+    }
+}
@@ -0,0 +1,312 @@
+use super::{
+    body_error, decode_packet, encode_head, packet_len, read_properties, write_properties,
+};
+use crate::data_representation::{ByteReader, ByteWriter, MqttDecode, MqttEncode, Utf8Validation};
+use crate::error::MqttError;
+use crate::fixed_header::{ControlPacketType, FixedHeader};
+use crate::properties::Properties;
+use core::fmt;
+
+// the only defined bit of the Connect Acknowledge Flags; bits 7-1 are reserved
+const SESSION_PRESENT: u8 = 0x01;
+
+/// The outcome of a connection attempt, reported in CONNACK.
+/// Codes of 0x80 and above mean the server refused the connection and will close it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum ConnectReasonCode {
+    Success = 0x00,
+    UnspecifiedError = 0x80,
+    MalformedPacket = 0x81,
+    ProtocolError = 0x82,
+    ImplementationSpecificError = 0x83,
+    UnsupportedProtocolVersion = 0x84,
+    ClientIdentifierNotValid = 0x85,
+    BadUserNameOrPassword = 0x86,
+    NotAuthorized = 0x87,
+    ServerUnavailable = 0x88,
+    ServerBusy = 0x89,
+    Banned = 0x8A,
+    BadAuthenticationMethod = 0x8C,
+    TopicNameInvalid = 0x90,
+    PacketTooLarge = 0x95,
+    QuotaExceeded = 0x97,
+    PayloadFormatInvalid = 0x99,
+    RetainNotSupported = 0x9A,
+    QoSNotSupported = 0x9B,
+    UseAnotherServer = 0x9C,
+    ServerMoved = 0x9D,
+    ConnectionRateExceeded = 0x9F,
+}
+
+impl ConnectReasonCode {
+    /// Returns whether the connection was accepted
+    pub const fn is_success(&self) -> bool {
+        matches!(self, ConnectReasonCode::Success)
+    }
+}
+
+impl TryFrom<u8> for ConnectReasonCode {
+    type Error = MqttError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(ConnectReasonCode::Success),
+            0x80 => Ok(ConnectReasonCode::UnspecifiedError),
+            0x81 => Ok(ConnectReasonCode::MalformedPacket),
+            0x82 => Ok(ConnectReasonCode::ProtocolError),
+            0x83 => Ok(ConnectReasonCode::ImplementationSpecificError),
+            0x84 => Ok(ConnectReasonCode::UnsupportedProtocolVersion),
+            0x85 => Ok(ConnectReasonCode::ClientIdentifierNotValid),
+            0x86 => Ok(ConnectReasonCode::BadUserNameOrPassword),
+            0x87 => Ok(ConnectReasonCode::NotAuthorized),
+            0x88 => Ok(ConnectReasonCode::ServerUnavailable),
+            0x89 => Ok(ConnectReasonCode::ServerBusy),
+            0x8A => Ok(ConnectReasonCode::Banned),
+            0x8C => Ok(ConnectReasonCode::BadAuthenticationMethod),
+            0x90 => Ok(ConnectReasonCode::TopicNameInvalid),
+            0x95 => Ok(ConnectReasonCode::PacketTooLarge),
+            0x97 => Ok(ConnectReasonCode::QuotaExceeded),
+            0x99 => Ok(ConnectReasonCode::PayloadFormatInvalid),
+            0x9A => Ok(ConnectReasonCode::RetainNotSupported),
+            0x9B => Ok(ConnectReasonCode::QoSNotSupported),
+            0x9C => Ok(ConnectReasonCode::UseAnotherServer),
+            0x9D => Ok(ConnectReasonCode::ServerMoved),
+            0x9F => Ok(ConnectReasonCode::ConnectionRateExceeded),
+            _ => Err(MqttError::InvalidReasonCode),
+        }
+    }
+}
+
+/// A CONNACK packet: the server's response to CONNECT, reporting whether the connection
+/// was accepted and whether an existing session was resumed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ConnAck<'a, const N: usize> {
+    pub session_present: bool, // only a successful CONNACK may resume a session
+    pub reason_code: ConnectReasonCode,
+    pub properties: Properties<'a, N>,
+}
+
+impl<'a, const N: usize> ConnAck<'a, N> {
+    /// Creates a response with the given reason code, no session present and no properties
+    pub const fn new(reason_code: ConnectReasonCode) -> Self {
+        Self {
+            session_present: false,
+            reason_code,
+            properties: Properties::new(),
+        }
+    }
+
+    // returns the encoded size of the variable header: the acknowledge flags,
+    // the reason code and the properties
+    pub(crate) fn body_len(&self) -> usize {
+        2 + self.properties.encoded_len()
+    }
+
+    // writes the variable header; CONNACK has no payload
+    pub(crate) fn encode_body(&self, writer: &mut ByteWriter) -> Result<(), MqttError> {
+        // a refused connection never has a session [MQTT-3.2.2-6]
+        if self.session_present && !self.reason_code.is_success() {
+            return Err(MqttError::InvalidSessionPresent);
+        }
+
+        writer.put_u8(if self.session_present {
+            SESSION_PRESENT
+        } else {
+            0
+        })?;
+        writer.put_u8(self.reason_code as u8)?;
+        write_properties(writer, &self.properties)?;
+
+        Ok(())
+    }
+
+    // reads the variable header, rejecting reserved flag bits and unknown reason codes
+    pub(crate) fn decode_body(reader: &mut ByteReader<'a>) -> Result<Self, MqttError> {
+        // bits 7-1 of the acknowledge flags are reserved and must be 0 [MQTT-3.2.2-1]
+        let flags = reader.read_u8().map_err(body_error)?;
+        if flags & !SESSION_PRESENT != 0 {
+            return Err(MqttError::MalformedPacket);
+        }
+        let session_present = flags & SESSION_PRESENT != 0;
+
+        let reason_code = ConnectReasonCode::try_from(reader.read_u8().map_err(body_error)?)
+            .map_err(|_| MqttError::MalformedPacket)?;
+        if session_present && !reason_code.is_success() {
+            return Err(MqttError::MalformedPacket);
+        }
+
+        let properties = read_properties(reader)?;
+
+        Ok(Self {
+            session_present,
+            reason_code,
+            properties,
+        })
+    }
+}
+
+impl<const N: usize> MqttEncode for ConnAck<'_, N> {
+    type Error = MqttError;
+
+    /// Encodes the complete CONNACK packet, fixed header included
+    /// Returns the number of bytes written
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        let header = FixedHeader::new(ControlPacketType::CONNACK)?;
+        encode_head(
+            &header,
+            self.body_len(),
+            0,
+            buffer,
+            Utf8Validation::Strict,
+            |writer| self.encode_body(writer),
+        )
+    }
+
+    fn encoded_len(&self) -> usize {
+        packet_len(self.body_len())
+    }
+}
+
+impl<'a, const N: usize> MqttDecode<'a> for ConnAck<'a, N> {
+    type Error = MqttError;
+
+    /// Decodes a complete CONNACK packet, fixed header included
+    /// Returns the packet and the number of bytes it occupied
+    fn decode(buffer: &'a [u8]) -> Result<(Self, usize), Self::Error> {
+        decode_packet(buffer, ControlPacketType::CONNACK, ConnAck::decode_body)
+    }
+}
+
+/// Formatted as the session present flag and reason code, followed by any properties
+impl<const N: usize> fmt::Display for ConnAck<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "session_present={} reason_code={:?}",
+            self.session_present, self.reason_code
+        )?;
+
+        if !self.properties.is_empty() {
+            write!(f, " properties={}", self.properties)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_connack {
+    use super::*;
+    use crate::packet::Packet;
+    use crate::properties::Property;
+
+    #[test]
+    fn test_reason_code_reversibility() {
+        for value in 0..=u8::MAX {
+            if let Ok(reason_code) = ConnectReasonCode::try_from(value) {
+                assert_eq!(reason_code as u8, value);
+            }
+        }
+
+        assert_eq!(
+            ConnectReasonCode::try_from(0x01),
+            Err(MqttError::InvalidReasonCode)
+        );
+    }
+
+    #[test]
+    fn test_encode_session_present() {
+        let mut connack = ConnAck::<0>::new(ConnectReasonCode::Success);
+        connack.session_present = true;
+
+        let mut buffer = [0; 8];
+        let len = connack.encode(&mut buffer).unwrap();
+
+        assert_eq!(len, connack.encoded_len());
+        assert_eq!(&buffer[..len], &[0x20, 0x03, 0x01, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn test_encode_rejects_session_present_on_failure() {
+        let mut connack = ConnAck::<0>::new(ConnectReasonCode::NotAuthorized);
+        connack.session_present = true;
+
+        let mut buffer = [0; 8];
+        assert_eq!(
+            connack.encode(&mut buffer),
+            Err(MqttError::InvalidSessionPresent)
+        );
+    }
+
+    #[test]
+    fn test_decode_reversibility() {
+        let mut properties = Properties::<2>::new();
+        properties
+            .push(Property::AssignedClientIdentifier("auto-1"))
+            .unwrap();
+        properties.push(Property::ServerKeepAlive(30)).unwrap();
+
+        let connack = ConnAck {
+            session_present: false,
+            reason_code: ConnectReasonCode::Success,
+            properties,
+        };
+
+        let mut buffer = [0; 32];
+        let len = connack.encode(&mut buffer).unwrap();
+
+        assert_eq!(
+            Packet::decode(&buffer[..len]),
+            Ok((Packet::ConnAck(connack), len))
+        );
+    }
+
+    #[test]
+    fn test_decode_refused() {
+        let buffer = [0x20, 0x03, 0x00, 0x87, 0x00];
+
+        assert_eq!(
+            ConnAck::<0>::decode(&buffer),
+            Ok((ConnAck::new(ConnectReasonCode::NotAuthorized), 5))
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_reserved_flags() {
+        for flags in [0x02, 0x80, 0xFF] {
+            assert_eq!(
+                ConnAck::<0>::decode(&[0x20, 0x03, flags, 0x00, 0x00]),
+                Err(MqttError::MalformedPacket)
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_rejects_session_present_on_failure() {
+        assert_eq!(
+            ConnAck::<0>::decode(&[0x20, 0x03, 0x01, 0x87, 0x00]),
+            Err(MqttError::MalformedPacket)
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_unknown_reason_code() {
+        assert_eq!(
+            ConnAck::<0>::decode(&[0x20, 0x03, 0x00, 0x01, 0x00]),
+            Err(MqttError::MalformedPacket)
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let connack = ConnAck::<0>::new(ConnectReasonCode::BadUserNameOrPassword);
+
+        assert_eq!(
+            format!("{}", Packet::ConnAck(connack)),
+            "CONNACK session_present=false reason_code=BadUserNameOrPassword"
+        );
+    }
+}
@@ -0,0 +1,701 @@
+use super::{
+    ClientId, KeepAlive, Missing, Will, body_error, decode_packet, encode_head, packet_len,
+    read_properties, write_properties,
+};
+use crate::data_representation::{
+    BinaryDataRef, ByteReader, ByteWriter, MqttDecode, MqttEncode, Utf8Validation,
+};
+use crate::error::MqttError;
+use crate::fixed_header::{ControlPacketType, FixedHeader, QOS};
+use crate::properties::Properties;
+use core::fmt;
+
+// every CONNECT opens with the protocol name and level, which identify MQTT 5
+const PROTOCOL_NAME: &str = "MQTT";
+const PROTOCOL_LEVEL: u8 = 5;
+
+// the keep alive used by `Connect::new`
+const DEFAULT_KEEP_ALIVE: KeepAlive = KeepAlive::from_secs(60);
+
+/// A CONNECT packet: the first packet a client sends, carrying its identity, session
+/// settings and, optionally, a Will message and credentials.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Connect<'a, const N: usize> {
+    pub clean_start: bool,
+    pub keep_alive: KeepAlive,
+    pub properties: Properties<'a, N>,
+    pub client_id: ClientId<'a>,
+    pub will: Option<Will<'a, N>>,
+    pub username: Option<&'a str>,
+    pub password: Option<&'a [u8]>,
+}
+
+/// The Connect Flags byte, stating which optional fields a CONNECT carries and how its
+/// session and Will are handled. Bit 0 is reserved, and always 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ConnectFlags {
+    pub username: bool,
+    pub password: bool,
+    pub will_retain: bool,
+    pub will_qos: QOS,
+    pub will: bool,
+    pub clean_start: bool,
+}
+
+impl ConnectFlags {
+    // bit positions within the flag byte
+    const USERNAME: u8 = 0x80;
+    const PASSWORD: u8 = 0x40;
+    const WILL_RETAIN: u8 = 0x20;
+    const WILL_QOS_SHIFT: u8 = 3;
+    const WILL: u8 = 0x04;
+    const CLEAN_START: u8 = 0x02;
+    const RESERVED: u8 = 0x01;
+
+    /// Returns the flag byte
+    pub const fn to_byte(&self) -> u8 {
+        let mut flags = (self.will_qos as u8) << Self::WILL_QOS_SHIFT;
+
+        if self.username {
+            flags |= Self::USERNAME;
+        }
+        if self.password {
+            flags |= Self::PASSWORD;
+        }
+        if self.will_retain {
+            flags |= Self::WILL_RETAIN;
+        }
+        if self.will {
+            flags |= Self::WILL;
+        }
+        if self.clean_start {
+            flags |= Self::CLEAN_START;
+        }
+
+        flags
+    }
+
+    /// Parses a flag byte, rejecting it as malformed if the reserved bit is set, the Will QoS
+    /// is 3, or the Will QoS or Will Retain is set without the Will Flag
+    pub const fn from_byte(flags: u8) -> Result<Self, MqttError> {
+        let will = flags & Self::WILL != 0;
+        let will_retain = flags & Self::WILL_RETAIN != 0;
+        let will_qos = match (flags >> Self::WILL_QOS_SHIFT) & 0x03 {
+            0 => QOS::ATMOSTONCE,
+            1 => QOS::ATLEASTONCE,
+            2 => QOS::EXACTLYONCE,
+            _ => return Err(MqttError::MalformedPacket),
+        };
+
+        // the reserved bit must be 0 [MQTT-3.1.2-3], and without a Will,
+        // its QoS and retain bits must be too [MQTT-3.1.2-11] [MQTT-3.1.2-13]
+        if flags & Self::RESERVED != 0
+            || (!will && (will_retain || !matches!(will_qos, QOS::ATMOSTONCE)))
+        {
+            return Err(MqttError::MalformedPacket);
+        }
+
+        Ok(Self {
+            username: flags & Self::USERNAME != 0,
+            password: flags & Self::PASSWORD != 0,
+            will_retain,
+            will_qos,
+            will,
+            clean_start: flags & Self::CLEAN_START != 0,
+        })
+    }
+}
+
+impl From<ConnectFlags> for u8 {
+    fn from(flags: ConnectFlags) -> Self {
+        flags.to_byte()
+    }
+}
+
+impl TryFrom<u8> for ConnectFlags {
+    type Error = MqttError;
+
+    fn try_from(flags: u8) -> Result<Self, Self::Error> {
+        Self::from_byte(flags)
+    }
+}
+
+impl<'a, const N: usize> Connect<'a, N> {
+    /// Creates a clean start connection request with a 60 second keep alive,
+    /// and no properties, Will or credentials
+    pub fn new(client_id: ClientId<'a>) -> Self {
+        Self {
+            clean_start: true,
+            keep_alive: DEFAULT_KEEP_ALIVE,
+            properties: Properties::new(),
+            client_id,
+            will: None,
+            username: None,
+            password: None,
+        }
+    }
+
+    /// Starts building a connection request, with the client identifier still to be set
+    pub const fn builder() -> ConnectBuilder<'a, N, Missing> {
+        ConnectBuilder {
+            client_id: Missing,
+            clean_start: true,
+            keep_alive: DEFAULT_KEEP_ALIVE,
+            properties: Properties::new(),
+            will: None,
+            username: None,
+            password: None,
+        }
+    }
+
+    /// Returns the Connect Flags describing this packet
+    pub fn flags(&self) -> ConnectFlags {
+        ConnectFlags {
+            username: self.username.is_some(),
+            password: self.password.is_some(),
+            will_retain: self.will.as_ref().is_some_and(|will| will.retain),
+            will_qos: self.will.as_ref().map_or(QOS::ATMOSTONCE, |will| will.qos),
+            will: self.will.is_some(),
+            clean_start: self.clean_start,
+        }
+    }
+
+    // returns the encoded size of the variable header and payload
+    pub(crate) fn body_len(&self) -> usize {
+        // protocol name, protocol level, connect flags and keep alive
+        let variable_header_len =
+            2 + PROTOCOL_NAME.len() + 1 + 1 + 2 + self.properties.encoded_len();
+
+        let will_len = self.will.as_ref().map_or(0, Will::body_len);
+        let username_len = self.username.map_or(0, |username| 2 + username.len());
+        let password_len = self.password.map_or(0, |password| 2 + password.len());
+
+        variable_header_len + 2 + self.client_id.len() + will_len + username_len + password_len
+    }
+
+    // writes the variable header, then the payload fields in the order the spec fixes
+    pub(crate) fn encode_body(&self, writer: &mut ByteWriter) -> Result<(), MqttError> {
+        writer.put_utf8_string(PROTOCOL_NAME)?;
+        writer.put_u8(PROTOCOL_LEVEL)?;
+        writer.put_u8(self.flags().to_byte())?;
+        writer.put_u16(self.keep_alive.as_secs())?;
+        write_properties(writer, &self.properties)?;
+
+        writer.put_utf8_string(self.client_id.as_str())?;
+
+        if let Some(will) = &self.will {
+            will.encode_body(writer)?;
+        }
+        if let Some(username) = self.username {
+            writer.put_utf8_string(username)?;
+        }
+        if let Some(password) = self.password {
+            writer.put_encoded(&BinaryDataRef::new(password)?)?;
+        }
+
+        Ok(())
+    }
+
+    // reads the variable header and payload, checking the flags agree with the fields present
+    pub(crate) fn decode_body(reader: &mut ByteReader<'a>) -> Result<Self, MqttError> {
+        // a different protocol name means this is not an MQTT connection at all [MQTT-3.1.2-1]
+        if reader.read_utf8_str().map_err(body_error)? != PROTOCOL_NAME {
+            return Err(MqttError::MalformedPacket);
+        }
+        if reader.read_u8().map_err(body_error)? != PROTOCOL_LEVEL {
+            return Err(MqttError::UnsupportedProtocolVersion);
+        }
+
+        let flags = ConnectFlags::from_byte(reader.read_u8().map_err(body_error)?)?;
+
+        let keep_alive = KeepAlive::from_secs(reader.read_u16().map_err(body_error)?);
+        let properties = read_properties(reader)?;
+
+        let client_id = ClientId::from_validated(reader.read_utf8_str().map_err(body_error)?);
+
+        let will = if flags.will {
+            Some(Will::decode_body(reader, &flags)?)
+        } else {
+            None
+        };
+
+        let username = if flags.username {
+            Some(reader.read_utf8_str().map_err(body_error)?)
+        } else {
+            None
+        };
+        let password = if flags.password {
+            Some(read_binary_data(reader)?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            clean_start: flags.clean_start,
+            keep_alive,
+            properties,
+            client_id,
+            will,
+            username,
+            password,
+        })
+    }
+}
+
+impl<const N: usize> MqttEncode for Connect<'_, N> {
+    type Error = MqttError;
+
+    /// Encodes the complete CONNECT packet, fixed header included
+    /// Returns the number of bytes written
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        let header = FixedHeader::new(ControlPacketType::CONNECT)?;
+        encode_head(
+            &header,
+            self.body_len(),
+            0,
+            buffer,
+            Utf8Validation::Strict,
+            |writer| self.encode_body(writer),
+        )
+    }
+
+    fn encoded_len(&self) -> usize {
+        packet_len(self.body_len())
+    }
+}
+
+impl<'a, const N: usize> MqttDecode<'a> for Connect<'a, N> {
+    type Error = MqttError;
+
+    /// Decodes a complete CONNECT packet, fixed header included
+    /// Returns the packet and the number of bytes it occupied
+    fn decode(buffer: &'a [u8]) -> Result<(Self, usize), Self::Error> {
+        decode_packet(buffer, ControlPacketType::CONNECT, Connect::decode_body)
+    }
+}
+
+fn read_binary_data<'a>(reader: &mut ByteReader<'a>) -> Result<&'a [u8], MqttError> {
+    let data: BinaryDataRef = reader.read_decoded().map_err(body_error)?;
+    Ok(data.as_bytes())
+}
+
+/// Formatted as the client identifier and session settings; the password is never shown
+impl<const N: usize> fmt::Display for Connect<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "client_id={:?} clean_start={} keep_alive={}",
+            self.client_id.as_str(),
+            self.clean_start,
+            self.keep_alive
+        )?;
+
+        if !self.properties.is_empty() {
+            write!(f, " properties={}", self.properties)?;
+        }
+        if let Some(will) = &self.will {
+            write!(
+                f,
+                " will_topic={:?} will_qos={}",
+                will.topic, will.qos as u8
+            )?;
+        }
+        if let Some(username) = self.username {
+            write!(f, " username={username:?}")?;
+        }
+        if self.password.is_some() {
+            f.write_str(" password=***")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds a `Connect`. `build` is only available once the client identifier is set:
+///
+/// ```compile_fail
+/// let connect = midge::packet::Connect::<0>::builder().clean_start(false).build();
+/// ```
+///
+/// As with `Connect::new`, the session starts clean with a 60 second keep alive, and there
+/// are no properties, Will or credentials.
+#[derive(Debug, Clone)]
+pub struct ConnectBuilder<'a, const N: usize, C> {
+    client_id: C,
+    clean_start: bool,
+    keep_alive: KeepAlive,
+    properties: Properties<'a, N>,
+    will: Option<Will<'a, N>>,
+    username: Option<&'a str>,
+    password: Option<&'a [u8]>,
+}
+
+impl<'a, const N: usize, C> ConnectBuilder<'a, N, C> {
+    /// Sets the identifier of the session
+    pub fn client_id(self, client_id: ClientId<'a>) -> ConnectBuilder<'a, N, ClientId<'a>> {
+        ConnectBuilder {
+            client_id,
+            clean_start: self.clean_start,
+            keep_alive: self.keep_alive,
+            properties: self.properties,
+            will: self.will,
+            username: self.username,
+            password: self.password,
+        }
+    }
+
+    /// Sets whether the server discards any existing session
+    pub fn clean_start(mut self, clean_start: bool) -> Self {
+        self.clean_start = clean_start;
+        self
+    }
+
+    /// Sets the longest the client may go without sending a packet
+    pub fn keep_alive(mut self, keep_alive: KeepAlive) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    /// Sets the properties
+    pub fn properties(mut self, properties: Properties<'a, N>) -> Self {
+        self.properties = properties;
+        self
+    }
+
+    /// Sets the Will the server publishes if the connection ends unexpectedly
+    pub fn will(mut self, will: Will<'a, N>) -> Self {
+        self.will = Some(will);
+        self
+    }
+
+    /// Sets the user name
+    pub fn username(mut self, username: &'a str) -> Self {
+        self.username = Some(username);
+        self
+    }
+
+    /// Sets the password
+    pub fn password(mut self, password: &'a [u8]) -> Self {
+        self.password = Some(password);
+        self
+    }
+}
+
+impl<'a, const N: usize> ConnectBuilder<'a, N, ClientId<'a>> {
+    /// Builds the connection request
+    pub fn build(self) -> Connect<'a, N> {
+        Connect {
+            clean_start: self.clean_start,
+            keep_alive: self.keep_alive,
+            properties: self.properties,
+            client_id: self.client_id,
+            will: self.will,
+            username: self.username,
+            password: self.password,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_connect_builder {
+    use super::*;
+
+    #[test]
+    fn test_builder_defaults() {
+        let client_id = ClientId::new("sensor1").unwrap();
+        let connect = Connect::<0>::builder().client_id(client_id).build();
+
+        assert_eq!(connect, Connect::new(client_id));
+    }
+
+    #[test]
+    fn test_builder_sets_every_field() {
+        let client_id = ClientId::new("sensor1").unwrap();
+        let will = Will::new("status", b"offline");
+
+        let connect = Connect::<0>::builder()
+            .clean_start(false)
+            .keep_alive(KeepAlive::from_secs(30))
+            .will(will.clone())
+            .username("user")
+            .password(b"secret")
+            .client_id(client_id)
+            .build();
+
+        assert_eq!(
+            connect,
+            Connect {
+                clean_start: false,
+                keep_alive: KeepAlive::from_secs(30),
+                properties: Properties::new(),
+                client_id,
+                will: Some(will),
+                username: Some("user"),
+                password: Some(b"secret"),
+            }
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_connect {
+    use super::*;
+    use crate::packet::Packet;
+    use crate::properties::Property;
+
+    #[test]
+    fn test_encode_minimal() {
+        let packet = Packet::<0>::Connect(Connect::new(ClientId::new("c1").unwrap()));
+
+        let mut buffer = [0; 32];
+        let len = packet.encode(&mut buffer).unwrap();
+
+        assert_eq!(
+            &buffer[..len],
+            &[
+                0x10, 0x0F, // CONNECT, remaining length 15
+                0x00, 0x04, b'M', b'Q', b'T', b'T', // protocol name
+                0x05, // protocol level
+                0x02, // connect flags: clean start
+                0x00, 0x3C, // keep alive of 60 seconds
+                0x00, // no properties
+                0x00, 0x02, b'c', b'1', // client identifier
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_with_will_and_credentials() {
+        let mut properties = Properties::<1>::new();
+        properties
+            .push(Property::SessionExpiryInterval(10))
+            .unwrap();
+
+        let mut will_properties = Properties::<1>::new();
+        will_properties
+            .push(Property::WillDelayInterval(5))
+            .unwrap();
+
+        let connect = Connect {
+            clean_start: false,
+            keep_alive: KeepAlive::disabled(),
+            properties,
+            client_id: ClientId::server_assigned(),
+            will: Some(Will {
+                properties: will_properties,
+                topic: "t",
+                payload: &[0xAA],
+                qos: QOS::ATLEASTONCE,
+                retain: true,
+            }),
+            username: Some("u"),
+            password: Some(b"p"),
+        };
+        let packet = Packet::Connect(connect);
+
+        let mut buffer = [0; 64];
+        let len = packet.encode(&mut buffer).unwrap();
+
+        assert_eq!(len, packet.encoded_len());
+        assert_eq!(
+            &buffer[..len],
+            &[
+                0x10, 0x24, // CONNECT, remaining length 36
+                0x00, 0x04, b'M', b'Q', b'T', b'T', // protocol name
+                0x05, // protocol level
+                0xEC, // connect flags: username, password, will retain, will QoS 1, will
+                0x00, 0x00, // keep alive disabled
+                0x05, 0x11, 0x00, 0x00, 0x00, 0x0A, // session expiry interval of 10 seconds
+                0x00, 0x00, // empty client identifier
+                0x05, 0x18, 0x00, 0x00, 0x00, 0x05, // will delay interval of 5 seconds
+                0x00, 0x01, b't', // will topic
+                0x00, 0x01, 0xAA, // will payload
+                0x00, 0x01, b'u', // username
+                0x00, 0x01, b'p', // password
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_standalone_matches_packet() {
+        let connect = Connect::<0>::new(ClientId::new("c1").unwrap());
+
+        let mut standalone = [0; 32];
+        let mut packet = [0; 32];
+        let len = connect.encode(&mut standalone).unwrap();
+
+        assert_eq!(len, connect.encoded_len());
+        assert_eq!(Packet::Connect(connect).encode(&mut packet), Ok(len));
+        assert_eq!(standalone, packet);
+    }
+
+    #[test]
+    fn test_display_hides_password() {
+        let mut connect = Connect::<0>::new(ClientId::new("c1").unwrap());
+        connect.username = Some("user");
+        connect.password = Some(b"secret");
+
+        assert_eq!(
+            format!("{connect}"),
+            "client_id=\"c1\" clean_start=true keep_alive=60 username=\"user\" password=***"
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_connect_decode {
+    use super::*;
+    use crate::packet::Packet;
+    use crate::properties::Property;
+
+    // CONNECT for client "c1" with a clean start and a 60 second keep alive,
+    // with the connect flags at index 9 left to each test
+    fn minimal_connect(flags: u8) -> [u8; 17] {
+        [
+            0x10, 0x0F, 0x00, 0x04, b'M', b'Q', b'T', b'T', 0x05, flags, 0x00, 0x3C, 0x00, 0x00,
+            0x02, b'c', b'1',
+        ]
+    }
+
+    #[test]
+    fn test_decode_minimal() {
+        let buffer = minimal_connect(0x02);
+
+        assert_eq!(
+            Connect::<0>::decode(&buffer),
+            Ok((Connect::new(ClientId::new("c1").unwrap()), buffer.len()))
+        );
+    }
+
+    #[test]
+    fn test_decode_reversibility() {
+        let mut will_properties = Properties::<2>::new();
+        will_properties
+            .push(Property::ContentType("text/plain"))
+            .unwrap();
+
+        let mut connect = Connect::<2>::new(ClientId::new("client").unwrap());
+        connect.clean_start = false;
+        connect.will = Some(Will {
+            properties: will_properties,
+            topic: "status",
+            payload: b"offline",
+            qos: QOS::EXACTLYONCE,
+            retain: false,
+        });
+        connect.username = Some("user");
+        connect.password = Some(b"password");
+
+        let mut buffer = [0; 96];
+        let len = connect.encode(&mut buffer).unwrap();
+
+        assert_eq!(
+            Packet::decode(&buffer[..len]),
+            Ok((Packet::Connect(connect), len))
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_other_protocols() {
+        let mut buffer = minimal_connect(0x02);
+        buffer[4] = b'm'; // protocol name "mQTT"
+        assert_eq!(
+            Connect::<0>::decode(&buffer),
+            Err(MqttError::MalformedPacket)
+        );
+
+        let mut buffer = minimal_connect(0x02);
+        buffer[8] = 0x04; // MQTT 3.1.1
+        assert_eq!(
+            Connect::<0>::decode(&buffer),
+            Err(MqttError::UnsupportedProtocolVersion)
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_flags() {
+        for flags in [
+            0x03, // reserved bit set
+            0x22, // will retain without a will
+            0x0A, // will QoS 1 without a will
+            0x1E, // will QoS 3
+        ] {
+            assert_eq!(
+                Connect::<0>::decode(&minimal_connect(flags)),
+                Err(MqttError::MalformedPacket)
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_rejects_missing_fields() {
+        // the username flag is set, but the packet ends after the client identifier
+        assert_eq!(
+            Connect::<0>::decode(&minimal_connect(0x82)),
+            Err(MqttError::MalformedPacket)
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_other_packet_types() {
+        assert_eq!(
+            Connect::<0>::decode(&[0xC0, 0x00]),
+            Err(MqttError::InvalidPacketType)
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_connect_flags {
+    use super::*;
+
+    #[test]
+    fn test_to_byte() {
+        let flags = ConnectFlags {
+            username: true,
+            password: false,
+            will_retain: true,
+            will_qos: QOS::EXACTLYONCE,
+            will: true,
+            clean_start: false,
+        };
+
+        assert_eq!(flags.to_byte(), 0xB4);
+        assert_eq!(u8::from(flags), 0xB4);
+    }
+
+    #[test]
+    fn test_reversibility() {
+        // every valid combination of the flags, with a Will of each QoS and without one
+        for flags in (0..=u8::MAX).filter_map(|byte| ConnectFlags::from_byte(byte).ok()) {
+            assert_eq!(ConnectFlags::try_from(flags.to_byte()), Ok(flags));
+        }
+    }
+
+    #[test]
+    fn test_from_byte_rejects_invalid_flags() {
+        for flags in [
+            0x01, // reserved bit set
+            0x20, // will retain without a will
+            0x08, // will QoS 1 without a will
+            0x10, // will QoS 2 without a will
+            0x1C, // will QoS 3
+        ] {
+            assert_eq!(
+                ConnectFlags::from_byte(flags),
+                Err(MqttError::MalformedPacket)
+            );
+        }
+    }
+
+    #[test]
+    fn test_connect_flags_match_fields() {
+        let mut connect = Connect::<0>::new(ClientId::new("c1").unwrap());
+        connect.password = Some(b"secret");
+
+        assert_eq!(connect.flags().to_byte(), 0x42);
+    }
+}
@@ -0,0 +1,257 @@
+use super::Packet;
+use crate::data_representation::MqttDecode;
+use crate::error::MqttError;
+use crate::fixed_header::FixedHeader;
+
+/// Incremental packet decoder, for callers receiving a stream of packets in arbitrary
+/// chunks (e.g. reads from a TCP socket). Bytes are buffered internally until a whole
+/// packet has arrived; packets longer than B bytes, or the configured maximum, are rejected
+/// as soon as their fixed header is read.
+#[derive(Debug, Clone)]
+pub struct PacketDecoder<const B: usize> {
+    buffer: [u8; B],
+    len: usize,                // number of bytes buffered for the current packet
+    packet_len: Option<usize>, // total length of the current packet, once its fixed header is read
+    complete: bool,            // whether the buffer holds a packet already returned to the caller
+    max_packet_size: usize,
+}
+
+impl<const B: usize> PacketDecoder<B> {
+    /// Creates a decoder accepting packets of up to B bytes
+    pub const fn new() -> Self {
+        Self::with_max_packet_size(B)
+    }
+
+    /// Creates a decoder accepting packets of up to `max_packet_size` bytes, capped at B
+    pub const fn with_max_packet_size(max_packet_size: usize) -> Self {
+        Self {
+            buffer: [0; B],
+            len: 0,
+            packet_len: None,
+            complete: false,
+            max_packet_size: if max_packet_size < B {
+                max_packet_size
+            } else {
+                B
+            },
+        }
+    }
+
+    /// Returns the largest packet, in bytes, the decoder accepts
+    pub fn max_packet_size(&self) -> usize {
+        self.max_packet_size
+    }
+
+    /// Returns whether part of a packet is buffered, awaiting more bytes
+    pub fn is_partial(&self) -> bool {
+        !self.complete && self.len > 0
+    }
+
+    /// Feeds received bytes, stopping at the end of the first packet they complete.
+    /// Returns the number of bytes consumed, and the packet once all of it has arrived;
+    /// any unconsumed bytes belong to the next packet and should be fed again.
+    /// On error the decoder is reset, discarding the partial packet.
+    pub fn feed<'a, const N: usize>(
+        &'a mut self,
+        bytes: &[u8],
+    ) -> Result<(usize, Option<Packet<'a, N>>), MqttError> {
+        // the previous packet was handed out on the last call, so its bytes can be reused
+        if self.complete {
+            self.reset();
+        }
+
+        let result = self.buffer_packet(bytes);
+        let consumed = match result {
+            Ok(consumed) => consumed,
+            Err(error) => {
+                self.reset();
+                return Err(error);
+            }
+        };
+
+        match self.packet_len {
+            Some(packet_len) if packet_len == self.len => {
+                self.complete = true;
+
+                let packet = self
+                    .buffer
+                    .get(..packet_len)
+                    .ok_or(MqttError::BufferOverflow)
+                    .and_then(Packet::decode)
+                    .map(|(packet, _)| packet)?;
+
+                Ok((consumed, Some(packet)))
+            }
+            _ => Ok((consumed, None)),
+        }
+    }
+
+    /// Discards any partially received packet
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.packet_len = None;
+        self.complete = false;
+    }
+
+    // copies as much of the current packet from `bytes` as is available.
+    // returns the number of bytes consumed
+    fn buffer_packet(&mut self, bytes: &[u8]) -> Result<usize, MqttError> {
+        let mut consumed = 0;
+
+        // the fixed header is at most 5 bytes, so it is read one byte at a time
+        // until its Remaining Length (and so the packet length) is known
+        while self.packet_len.is_none() {
+            let Some(&byte) = bytes.get(consumed) else {
+                return Ok(consumed);
+            };
+            self.push(byte)?;
+            consumed += 1;
+
+            match FixedHeader::decode(self.buffer.get(..self.len).unwrap_or_default()) {
+                Ok((_, remaining_length, header_len)) => {
+                    let packet_len = header_len + remaining_length as usize;
+                    if packet_len > self.max_packet_size {
+                        return Err(MqttError::PacketTooLarge);
+                    }
+                    self.packet_len = Some(packet_len);
+                }
+                Err(MqttError::IncompletePacket) => {}
+                Err(error) => return Err(error),
+            }
+        }
+
+        let packet_len = self.packet_len.unwrap_or_default();
+        let available = bytes.get(consumed..).unwrap_or_default();
+        let wanted = packet_len.saturating_sub(self.len).min(available.len());
+
+        let (source, target) = available
+            .get(..wanted)
+            .zip(self.buffer.get_mut(self.len..self.len + wanted))
+            .ok_or(MqttError::PacketTooLarge)?;
+        target.copy_from_slice(source);
+        self.len += wanted;
+
+        Ok(consumed + wanted)
+    }
+
+    fn push(&mut self, byte: u8) -> Result<(), MqttError> {
+        let slot = self
+            .buffer
+            .get_mut(self.len)
+            .ok_or(MqttError::PacketTooLarge)?;
+        *slot = byte;
+        self.len += 1;
+
+        Ok(())
+    }
+}
+
+impl<const B: usize> Default for PacketDecoder<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test_packet_decoder {
+    use super::*;
+    use crate::fixed_header::ControlPacketType;
+    use crate::packet::PublishResponse;
+
+    const PUBCOMP: [u8; 5] = [0x70, 0x03, 0x00, 0x05, 0x92];
+
+    #[test]
+    fn test_whole_packet() {
+        let mut decoder = PacketDecoder::<16>::new();
+
+        let (consumed, packet) = decoder.feed::<0>(&PUBCOMP).unwrap();
+
+        assert_eq!(consumed, 5);
+        assert_eq!(
+            packet,
+            Some(Packet::PubComp(PublishResponse {
+                packet_identifier: 5,
+                reason_code: 0x92,
+                properties: Default::default(),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_one_byte_at_a_time() {
+        let mut decoder = PacketDecoder::<16>::new();
+
+        for byte in &PUBCOMP[..4] {
+            assert_eq!(decoder.feed::<0>(&[*byte]), Ok((1, None)));
+            assert!(decoder.is_partial());
+        }
+
+        let (_, packet) = decoder.feed::<0>(&PUBCOMP[4..]).unwrap();
+        assert!(matches!(packet, Some(Packet::PubComp(_))));
+    }
+
+    #[test]
+    fn test_split_remaining_length() {
+        let mut buffer = [0; 140];
+        buffer[..3].copy_from_slice(&[0xC0, 0x80, 0x01]); // PINGREQ claiming 128 bytes
+
+        let mut decoder = PacketDecoder::<256>::new();
+        assert_eq!(decoder.feed::<0>(&buffer[..2]), Ok((2, None)));
+        assert_eq!(decoder.feed::<0>(&buffer[2..100]), Ok((98, None)));
+
+        // the body is read in full before it is found to be malformed
+        assert_eq!(
+            decoder.feed::<0>(&buffer[100..]),
+            Err(MqttError::MalformedPacket)
+        );
+        assert!(!decoder.is_partial());
+    }
+
+    #[test]
+    fn test_back_to_back_packets() {
+        let mut stream = [0; 9];
+        stream[..2].copy_from_slice(&[0xC0, 0x00]); // PINGREQ
+        stream[2..7].copy_from_slice(&PUBCOMP);
+        stream[7..].copy_from_slice(&[0xD0, 0x00]); // PINGRESP
+
+        let mut decoder = PacketDecoder::<16>::new();
+        let mut remaining = &stream[..];
+        let mut packet_types = [None; 3];
+
+        for packet_type in &mut packet_types {
+            let (consumed, packet) = decoder.feed::<0>(remaining).unwrap();
+            *packet_type = packet.map(|packet| packet.packet_type());
+            remaining = &remaining[consumed..];
+        }
+
+        assert!(remaining.is_empty());
+        assert_eq!(
+            packet_types,
+            [
+                Some(ControlPacketType::PINGREQ),
+                Some(ControlPacketType::PUBCOMP),
+                Some(ControlPacketType::PINGRESP),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rejects_packet_over_maximum_size() {
+        let mut decoder = PacketDecoder::<16>::with_max_packet_size(4);
+
+        assert_eq!(decoder.max_packet_size(), 4);
+        assert_eq!(decoder.feed::<0>(&PUBCOMP), Err(MqttError::PacketTooLarge));
+
+        // the decoder recovers for the next packet
+        assert_eq!(
+            decoder.feed::<0>(&[0xC0, 0x00]),
+            Ok((2, Some(Packet::PingReq)))
+        );
+    }
+
+    #[test]
+    fn test_maximum_size_is_capped_at_capacity() {
+        let decoder = PacketDecoder::<16>::with_max_packet_size(1024);
+        assert_eq!(decoder.max_packet_size(), 16);
+    }
+}
@@ -0,0 +1,367 @@
+use super::{
+    body_error, decode_packet, encode_head, packet_len, read_properties, write_properties,
+};
+use crate::data_representation::{ByteReader, ByteWriter, MqttDecode, MqttEncode, Utf8Validation};
+use crate::error::MqttError;
+use crate::fixed_header::{ControlPacketType, FixedHeader};
+use crate::properties::{Properties, Property};
+use core::fmt;
+
+/// Why a connection is being closed, sent in DISCONNECT by either side.
+/// Codes of 0x80 and above report an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum DisconnectReasonCode {
+    #[default]
+    NormalDisconnection = 0x00,
+    DisconnectWithWillMessage = 0x04, // the client asks the server to publish its Will anyway
+    UnspecifiedError = 0x80,
+    MalformedPacket = 0x81,
+    ProtocolError = 0x82,
+    ImplementationSpecificError = 0x83,
+    NotAuthorized = 0x87,
+    ServerBusy = 0x89,
+    ServerShuttingDown = 0x8B,
+    KeepAliveTimeout = 0x8D,
+    SessionTakenOver = 0x8E,
+    TopicFilterInvalid = 0x8F,
+    TopicNameInvalid = 0x90,
+    ReceiveMaximumExceeded = 0x93,
+    TopicAliasInvalid = 0x94,
+    PacketTooLarge = 0x95,
+    MessageRateTooHigh = 0x96,
+    QuotaExceeded = 0x97,
+    AdministrativeAction = 0x98,
+    PayloadFormatInvalid = 0x99,
+    RetainNotSupported = 0x9A,
+    QoSNotSupported = 0x9B,
+    UseAnotherServer = 0x9C,
+    ServerMoved = 0x9D,
+    SharedSubscriptionsNotSupported = 0x9E,
+    ConnectionRateExceeded = 0x9F,
+    MaximumConnectTime = 0xA0,
+    SubscriptionIdentifiersNotSupported = 0xA1,
+    WildcardSubscriptionsNotSupported = 0xA2,
+}
+
+impl TryFrom<u8> for DisconnectReasonCode {
+    type Error = MqttError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(DisconnectReasonCode::NormalDisconnection),
+            0x04 => Ok(DisconnectReasonCode::DisconnectWithWillMessage),
+            0x80 => Ok(DisconnectReasonCode::UnspecifiedError),
+            0x81 => Ok(DisconnectReasonCode::MalformedPacket),
+            0x82 => Ok(DisconnectReasonCode::ProtocolError),
+            0x83 => Ok(DisconnectReasonCode::ImplementationSpecificError),
+            0x87 => Ok(DisconnectReasonCode::NotAuthorized),
+            0x89 => Ok(DisconnectReasonCode::ServerBusy),
+            0x8B => Ok(DisconnectReasonCode::ServerShuttingDown),
+            0x8D => Ok(DisconnectReasonCode::KeepAliveTimeout),
+            0x8E => Ok(DisconnectReasonCode::SessionTakenOver),
+            0x8F => Ok(DisconnectReasonCode::TopicFilterInvalid),
+            0x90 => Ok(DisconnectReasonCode::TopicNameInvalid),
+            0x93 => Ok(DisconnectReasonCode::ReceiveMaximumExceeded),
+            0x94 => Ok(DisconnectReasonCode::TopicAliasInvalid),
+            0x95 => Ok(DisconnectReasonCode::PacketTooLarge),
+            0x96 => Ok(DisconnectReasonCode::MessageRateTooHigh),
+            0x97 => Ok(DisconnectReasonCode::QuotaExceeded),
+            0x98 => Ok(DisconnectReasonCode::AdministrativeAction),
+            0x99 => Ok(DisconnectReasonCode::PayloadFormatInvalid),
+            0x9A => Ok(DisconnectReasonCode::RetainNotSupported),
+            0x9B => Ok(DisconnectReasonCode::QoSNotSupported),
+            0x9C => Ok(DisconnectReasonCode::UseAnotherServer),
+            0x9D => Ok(DisconnectReasonCode::ServerMoved),
+            0x9E => Ok(DisconnectReasonCode::SharedSubscriptionsNotSupported),
+            0x9F => Ok(DisconnectReasonCode::ConnectionRateExceeded),
+            0xA0 => Ok(DisconnectReasonCode::MaximumConnectTime),
+            0xA1 => Ok(DisconnectReasonCode::SubscriptionIdentifiersNotSupported),
+            0xA2 => Ok(DisconnectReasonCode::WildcardSubscriptionsNotSupported),
+            _ => Err(MqttError::InvalidReasonCode),
+        }
+    }
+}
+
+/// A DISCONNECT packet: the last packet sent before closing the connection, from either side.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Disconnect<'a, const N: usize> {
+    pub reason_code: DisconnectReasonCode,
+    pub properties: Properties<'a, N>,
+}
+
+impl<'a, const N: usize> Disconnect<'a, N> {
+    /// Creates a disconnect with the given reason code and no properties
+    pub const fn new(reason_code: DisconnectReasonCode) -> Self {
+        Self {
+            reason_code,
+            properties: Properties::new(),
+        }
+    }
+
+    /// Returns the Session Expiry Interval, in seconds, if the properties set one
+    pub fn session_expiry_interval(&self) -> Option<u32> {
+        self.properties.iter().find_map(|property| match property {
+            Property::SessionExpiryInterval(secs) => Some(*secs),
+            _ => None,
+        })
+    }
+
+    /// Returns the Reason String, if the properties set one
+    pub fn reason_string(&self) -> Option<&'a str> {
+        self.properties.iter().find_map(|property| match property {
+            Property::ReasonString(reason) => Some(*reason),
+            _ => None,
+        })
+    }
+
+    /// Returns the Server Reference, the server to use instead, if the properties set one
+    pub fn server_reference(&self) -> Option<&'a str> {
+        self.properties.iter().find_map(|property| match property {
+            Property::ServerReference(server) => Some(*server),
+            _ => None,
+        })
+    }
+
+    // returns the encoded size of the variable header, omitting fields as encode_body does
+    pub(crate) fn body_len(&self) -> usize {
+        if !self.properties.is_empty() {
+            1 + self.properties.encoded_len()
+        } else if self.reason_code != DisconnectReasonCode::NormalDisconnection {
+            1
+        } else {
+            0
+        }
+    }
+
+    // writes the variable header; DISCONNECT has no payload.
+    // trailing fields are omitted where the spec allows: a Normal Disconnection with no
+    // properties has an empty body, and the property length is left out with no properties
+    pub(crate) fn encode_body(&self, writer: &mut ByteWriter) -> Result<(), MqttError> {
+        if !self.properties.iter().all(is_disconnect_property) {
+            return Err(MqttError::InvalidPropertyIdentifier);
+        }
+
+        if self.properties.is_empty() {
+            if self.reason_code != DisconnectReasonCode::NormalDisconnection {
+                writer.put_u8(self.reason_code as u8)?;
+            }
+        } else {
+            writer.put_u8(self.reason_code as u8)?;
+            write_properties(writer, &self.properties)?;
+        }
+
+        Ok(())
+    }
+
+    // reads the variable header, applying the defaults for omitted trailing fields
+    pub(crate) fn decode_body(reader: &mut ByteReader<'a>) -> Result<Self, MqttError> {
+        let reason_code = if reader.is_empty() {
+            DisconnectReasonCode::NormalDisconnection
+        } else {
+            DisconnectReasonCode::try_from(reader.read_u8().map_err(body_error)?)
+                .map_err(|_| MqttError::MalformedPacket)?
+        };
+
+        let properties: Properties<'a, N> = if reader.is_empty() {
+            Properties::new()
+        } else {
+            read_properties(reader)?
+        };
+        if !properties.iter().all(is_disconnect_property) {
+            return Err(MqttError::MalformedProperties);
+        }
+
+        Ok(Self {
+            reason_code,
+            properties,
+        })
+    }
+}
+
+// whether the property may appear in DISCONNECT
+fn is_disconnect_property(property: &Property) -> bool {
+    matches!(
+        property,
+        Property::SessionExpiryInterval(_)
+            | Property::ReasonString(_)
+            | Property::ServerReference(_)
+            | Property::UserProperty(_, _)
+    )
+}
+
+impl<const N: usize> MqttEncode for Disconnect<'_, N> {
+    type Error = MqttError;
+
+    /// Encodes the complete DISCONNECT packet, fixed header included
+    /// Returns the number of bytes written
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        let header = FixedHeader::new(ControlPacketType::DISCONNECT)?;
+        encode_head(
+            &header,
+            self.body_len(),
+            0,
+            buffer,
+            Utf8Validation::Strict,
+            |writer| self.encode_body(writer),
+        )
+    }
+
+    fn encoded_len(&self) -> usize {
+        packet_len(self.body_len())
+    }
+}
+
+impl<'a, const N: usize> MqttDecode<'a> for Disconnect<'a, N> {
+    type Error = MqttError;
+
+    /// Decodes a complete DISCONNECT packet, fixed header included
+    /// Returns the packet and the number of bytes it occupied
+    fn decode(buffer: &'a [u8]) -> Result<(Self, usize), Self::Error> {
+        decode_packet(
+            buffer,
+            ControlPacketType::DISCONNECT,
+            Disconnect::decode_body,
+        )
+    }
+}
+
+/// Formatted as the reason code, followed by any properties
+impl<const N: usize> fmt::Display for Disconnect<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "reason_code={:?}", self.reason_code)?;
+
+        if !self.properties.is_empty() {
+            write!(f, " properties={}", self.properties)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_disconnect {
+    use super::*;
+    use crate::packet::Packet;
+
+    #[test]
+    fn test_reason_code_reversibility() {
+        for value in 0..=u8::MAX {
+            if let Ok(reason_code) = DisconnectReasonCode::try_from(value) {
+                assert_eq!(reason_code as u8, value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_normal_disconnection_shorthand() {
+        let disconnect = Disconnect::<0>::default();
+
+        let mut buffer = [0; 4];
+        let len = disconnect.encode(&mut buffer).unwrap();
+
+        assert_eq!(len, disconnect.encoded_len());
+        assert_eq!(&buffer[..len], &[0xE0, 0x00]);
+    }
+
+    #[test]
+    fn test_encode_omits_property_length() {
+        let disconnect = Disconnect::<0>::new(DisconnectReasonCode::ServerShuttingDown);
+
+        let mut buffer = [0; 4];
+        let len = disconnect.encode(&mut buffer).unwrap();
+
+        assert_eq!(&buffer[..len], &[0xE0, 0x01, 0x8B]);
+    }
+
+    #[test]
+    fn test_encode_with_properties() {
+        let mut disconnect = Disconnect::<2>::new(DisconnectReasonCode::UseAnotherServer);
+        disconnect
+            .properties
+            .push(Property::ServerReference("b"))
+            .unwrap();
+
+        let mut buffer = [0; 16];
+        let len = disconnect.encode(&mut buffer).unwrap();
+
+        assert_eq!(
+            &buffer[..len],
+            &[
+                0xE0, 0x06, // DISCONNECT, remaining length 6
+                0x9C, // use another server
+                0x04, 0x1C, 0x00, 0x01, b'b', // server reference
+            ]
+        );
+        assert_eq!(disconnect.server_reference(), Some("b"));
+    }
+
+    #[test]
+    fn test_encode_rejects_other_properties() {
+        let mut disconnect = Disconnect::<1>::default();
+        disconnect.properties.push(Property::TopicAlias(1)).unwrap();
+
+        let mut buffer = [0; 16];
+        assert_eq!(
+            disconnect.encode(&mut buffer),
+            Err(MqttError::InvalidPropertyIdentifier)
+        );
+    }
+
+    #[test]
+    fn test_decode_applies_defaults() {
+        assert_eq!(
+            Disconnect::<0>::decode(&[0xE0, 0x00]),
+            Ok((Disconnect::default(), 2))
+        );
+        assert_eq!(
+            Disconnect::<0>::decode(&[0xE0, 0x01, 0x04]),
+            Ok((
+                Disconnect::new(DisconnectReasonCode::DisconnectWithWillMessage),
+                3
+            ))
+        );
+    }
+
+    #[test]
+    fn test_decode_reversibility() {
+        let mut disconnect = Disconnect::<2>::new(DisconnectReasonCode::NormalDisconnection);
+        disconnect
+            .properties
+            .push(Property::SessionExpiryInterval(300))
+            .unwrap();
+        disconnect
+            .properties
+            .push(Property::ReasonString("bye"))
+            .unwrap();
+
+        let packet = Packet::Disconnect(disconnect);
+        let mut buffer = [0; 32];
+        let len = packet.encode(&mut buffer).unwrap();
+
+        let (decoded, _) = Disconnect::<2>::decode(&buffer[..len]).unwrap();
+        assert_eq!(decoded.session_expiry_interval(), Some(300));
+        assert_eq!(decoded.reason_string(), Some("bye"));
+        assert_eq!(Packet::decode(&buffer[..len]), Ok((packet, len)));
+    }
+
+    #[test]
+    fn test_decode_rejects_unknown_reason_code() {
+        assert_eq!(
+            Disconnect::<0>::decode(&[0xE0, 0x01, 0x01]),
+            Err(MqttError::MalformedPacket)
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let disconnect = Disconnect::<0>::new(DisconnectReasonCode::KeepAliveTimeout);
+
+        assert_eq!(
+            format!("{}", Packet::Disconnect(disconnect)),
+            "DISCONNECT reason_code=KeepAliveTimeout"
+        );
+    }
+}
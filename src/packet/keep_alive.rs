@@ -0,0 +1,140 @@
+use crate::error::MqttError;
+use core::fmt;
+use core::time::Duration;
+
+/// The Keep Alive sent in CONNECT: the longest interval, in seconds, the client may go
+/// without sending a packet. If the server hears nothing for one and a half times this
+/// interval it closes the connection. Zero disables the mechanism.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct KeepAlive(u16);
+
+impl KeepAlive {
+    /// Creates a keep alive of the given number of seconds
+    pub const fn from_secs(secs: u16) -> Self {
+        Self(secs)
+    }
+
+    /// Creates a keep alive of 0, disabling the keep alive mechanism
+    pub const fn disabled() -> Self {
+        Self(0)
+    }
+
+    /// Returns the keep alive in seconds
+    pub const fn as_secs(&self) -> u16 {
+        self.0
+    }
+
+    /// Returns whether the keep alive mechanism is disabled
+    pub const fn is_disabled(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the keep alive interval, or None if the mechanism is disabled
+    pub const fn interval(&self) -> Option<Duration> {
+        if self.is_disabled() {
+            None
+        } else {
+            Some(Duration::from_secs(self.0 as u64))
+        }
+    }
+}
+
+impl From<u16> for KeepAlive {
+    fn from(secs: u16) -> Self {
+        Self(secs)
+    }
+}
+
+impl From<KeepAlive> for u16 {
+    fn from(keep_alive: KeepAlive) -> Self {
+        keep_alive.0
+    }
+}
+
+/// A disabled keep alive converts to a zero duration
+impl From<KeepAlive> for Duration {
+    fn from(keep_alive: KeepAlive) -> Self {
+        Duration::from_secs(keep_alive.0 as u64)
+    }
+}
+
+/// Fractions of a second are rounded up, so a short, non-zero duration never disables
+/// the keep alive. Durations over 65535 seconds are rejected.
+impl TryFrom<Duration> for KeepAlive {
+    type Error = MqttError;
+
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        let secs = duration
+            .as_secs()
+            .checked_add(u64::from(duration.subsec_nanos() > 0))
+            .ok_or(MqttError::InvalidKeepAlive)?;
+
+        u16::try_from(secs)
+            .map(Self)
+            .map_err(|_| MqttError::InvalidKeepAlive)
+    }
+}
+
+/// Formatted as the number of seconds
+impl fmt::Display for KeepAlive {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod test_keep_alive {
+    use super::*;
+
+    #[test]
+    fn test_disabled() {
+        assert!(KeepAlive::disabled().is_disabled());
+        assert_eq!(KeepAlive::disabled().interval(), None);
+        assert_eq!(Duration::from(KeepAlive::disabled()), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_duration_conversions() {
+        let keep_alive = KeepAlive::from_secs(90);
+
+        assert_eq!(keep_alive.interval(), Some(Duration::from_secs(90)));
+        assert_eq!(
+            KeepAlive::try_from(Duration::from(keep_alive)),
+            Ok(keep_alive)
+        );
+    }
+
+    #[test]
+    fn test_try_from_duration_rounds_up() {
+        assert_eq!(
+            KeepAlive::try_from(Duration::from_millis(1)),
+            Ok(KeepAlive::from_secs(1))
+        );
+        assert_eq!(
+            KeepAlive::try_from(Duration::from_millis(2500)),
+            Ok(KeepAlive::from_secs(3))
+        );
+    }
+
+    #[test]
+    fn test_try_from_duration_rejects_overlong() {
+        assert_eq!(
+            KeepAlive::try_from(Duration::from_secs(65535)),
+            Ok(KeepAlive::from_secs(u16::MAX))
+        );
+        assert_eq!(
+            KeepAlive::try_from(Duration::from_secs(65536)),
+            Err(MqttError::InvalidKeepAlive)
+        );
+        assert_eq!(
+            KeepAlive::try_from(Duration::from_millis(65_535_001)),
+            Err(MqttError::InvalidKeepAlive)
+        );
+        assert_eq!(
+            KeepAlive::try_from(Duration::MAX),
+            Err(MqttError::InvalidKeepAlive)
+        );
+    }
+}
@@ -0,0 +1,828 @@
+//! Complete MQTT control packets. Each packet is encoded as its fixed header, followed by
+//! the variable header, properties and payload; the Remaining Length in the fixed header
+//! is computed from whatever follows it, so callers never supply it.
+
+mod client_id;
+mod connack;
+mod connect;
+mod decoder;
+mod disconnect;
+mod keep_alive;
+mod ping;
+mod publish;
+mod publish_response;
+mod suback;
+mod subscribe;
+mod will;
+
+pub use client_id::ClientId;
+pub use connack::{ConnAck, ConnectReasonCode};
+pub use connect::{Connect, ConnectBuilder, ConnectFlags};
+pub use decoder::PacketDecoder;
+pub use disconnect::{Disconnect, DisconnectReasonCode};
+pub use keep_alive::KeepAlive;
+pub use ping::{PingReq, PingResp};
+pub use publish::{Publish, PublishBuilder};
+pub use publish_response::{PublishResponse, PublishResponseBuilder};
+pub use suback::{SubAck, SubscribeReasonCode};
+pub use subscribe::{RetainHandling, Subscribe, Subscription, SubscriptionOptions};
+pub use will::{Will, WillBuilder};
+
+use crate::data_representation::{
+    ByteReader, ByteWriter, DataRepresentationError, MqttDecode, MqttEncode, Utf8Validation,
+    VariableByteInt,
+};
+use crate::error::MqttError;
+use crate::fixed_header::{ControlPacketType, FixedHeader};
+use crate::properties::Properties;
+use core::fmt;
+
+/// Marks a required field a packet builder has not been given yet. Builders only offer
+/// `build` once every such field is set, so incomplete packets fail to compile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Missing;
+
+/// An MQTT control packet. Properties, and lists such as the topic filters of SUBSCRIBE
+/// and the reason codes of SUBACK, hold up to N entries; string and binary fields borrow
+/// from the caller (or from the buffer the packet was decoded from).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Packet<'a, const N: usize> {
+    Connect(Connect<'a, N>),
+    ConnAck(ConnAck<'a, N>),
+    Publish(Publish<'a, N>),
+    PubAck(PublishResponse<'a, N>),
+    PubRec(PublishResponse<'a, N>),
+    PubRel(PublishResponse<'a, N>),
+    PubComp(PublishResponse<'a, N>),
+    Subscribe(Subscribe<'a, N>),
+    SubAck(SubAck<'a, N>),
+    Disconnect(Disconnect<'a, N>),
+    PingReq,
+    PingResp,
+}
+
+impl<'a, const N: usize> Packet<'a, N> {
+    /// Returns the type of this packet
+    pub fn packet_type(&self) -> ControlPacketType {
+        match self {
+            Packet::Connect(_) => ControlPacketType::CONNECT,
+            Packet::ConnAck(_) => ControlPacketType::CONNACK,
+            Packet::Publish(_) => ControlPacketType::PUBLISH,
+            Packet::PubAck(_) => ControlPacketType::PUBACK,
+            Packet::PubRec(_) => ControlPacketType::PUBREC,
+            Packet::PubRel(_) => ControlPacketType::PUBREL,
+            Packet::PubComp(_) => ControlPacketType::PUBCOMP,
+            Packet::Subscribe(_) => ControlPacketType::SUBSCRIBE,
+            Packet::SubAck(_) => ControlPacketType::SUBACK,
+            Packet::Disconnect(_) => ControlPacketType::DISCONNECT,
+            Packet::PingReq => ControlPacketType::PINGREQ,
+            Packet::PingResp => ControlPacketType::PINGRESP,
+        }
+    }
+
+    /// Returns the fixed header this packet is encoded with
+    pub fn fixed_header(&self) -> Result<FixedHeader, MqttError> {
+        match self {
+            Packet::Publish(publish) => publish.fixed_header(),
+            _ => FixedHeader::new(self.packet_type()),
+        }
+    }
+
+    /// Encodes the complete packet, refusing to write one larger than `max_packet_size` bytes
+    /// (e.g. the Maximum Packet Size the receiver announced)
+    /// Returns the number of bytes written
+    pub fn encode_with_max_packet_size(
+        &self,
+        buffer: &mut [u8],
+        max_packet_size: usize,
+    ) -> Result<usize, MqttError> {
+        if self.encoded_len() > max_packet_size {
+            return Err(MqttError::PacketTooLarge);
+        }
+
+        self.encode(buffer)
+    }
+
+    /// Decodes a complete packet, rejecting one larger than `max_packet_size` bytes as soon as
+    /// its fixed header is read, before the rest of it needs to be available
+    /// Returns the packet and the number of bytes it occupied, including the fixed header
+    pub fn decode_with_max_packet_size(
+        buffer: &'a [u8],
+        max_packet_size: usize,
+    ) -> Result<(Self, usize), MqttError> {
+        let (header, body, packet_len) = split_packet(buffer, max_packet_size)?;
+
+        Ok((
+            Packet::decode_body(&header, body, Utf8Validation::Strict)?,
+            packet_len,
+        ))
+    }
+
+    /// Decodes a complete packet, validating its strings as given rather than strictly,
+    /// e.g. to accept control characters from a peer known to send them
+    /// Returns the packet and the number of bytes it occupied, including the fixed header
+    pub fn decode_with_validation(
+        buffer: &'a [u8],
+        validation: Utf8Validation,
+    ) -> Result<(Self, usize), MqttError> {
+        let (header, body, packet_len) = split_packet(buffer, usize::MAX)?;
+
+        Ok((Packet::decode_body(&header, body, validation)?, packet_len))
+    }
+
+    /// Encodes the complete packet, validating its strings as given rather than strictly,
+    /// e.g. to pass on a packet decoded with the same validation
+    /// Returns the number of bytes written
+    pub fn encode_with_validation(
+        &self,
+        buffer: &mut [u8],
+        validation: Utf8Validation,
+    ) -> Result<usize, MqttError> {
+        // check for room up front, so the payload always fits after the header bytes
+        let packet = buffer
+            .get_mut(..self.encoded_len())
+            .ok_or(MqttError::BufferOverflow)?;
+
+        let (head_len, payload) = self.encode_head(packet, validation)?;
+        packet
+            .get_mut(head_len..head_len + payload.len())
+            .ok_or(MqttError::BufferOverflow)?
+            .copy_from_slice(payload);
+
+        Ok(head_len + payload.len())
+    }
+
+    /// Encodes everything but the application payload, which is returned borrowed rather than
+    /// copied, so transports can send both parts with a single vectored write
+    /// Returns the number of bytes written, and the payload to send straight after them
+    pub fn encode_vectored(&self, buffer: &mut [u8]) -> Result<(usize, &'a [u8]), MqttError> {
+        self.encode_head(buffer, Utf8Validation::Strict)
+    }
+
+    // writes everything but the application payload, which is returned for the caller to send
+    fn encode_head(
+        &self,
+        buffer: &mut [u8],
+        validation: Utf8Validation,
+    ) -> Result<(usize, &'a [u8]), MqttError> {
+        let payload = self.payload();
+        let head_len = encode_head(
+            &self.fixed_header()?,
+            self.body_len(),
+            payload.len(),
+            buffer,
+            validation,
+            |writer| self.encode_body(writer),
+        )?;
+
+        Ok((head_len, payload))
+    }
+
+    /// Encodes the packet as the slices to pass to `write_vectored`: the header bytes,
+    /// written into the buffer, followed by the borrowed application payload
+    #[cfg(feature = "std")]
+    pub fn encode_io_slices<'b>(
+        &self,
+        buffer: &'b mut [u8],
+    ) -> Result<[std::io::IoSlice<'b>; 2], MqttError>
+    where
+        'a: 'b,
+    {
+        let (head_len, payload) = self.encode_vectored(buffer)?;
+        let buffer: &'b [u8] = buffer;
+        let head = buffer.get(..head_len).ok_or(MqttError::BufferOverflow)?;
+
+        Ok([std::io::IoSlice::new(head), std::io::IoSlice::new(payload)])
+    }
+
+    // returns the application payload, the trailing part of the body that vectored encoding
+    // leaves in place. only PUBLISH carries one
+    fn payload(&self) -> &'a [u8] {
+        match self {
+            Packet::Publish(publish) => publish.payload,
+            Packet::Connect(_)
+            | Packet::ConnAck(_)
+            | Packet::PubAck(_)
+            | Packet::PubRec(_)
+            | Packet::PubRel(_)
+            | Packet::PubComp(_)
+            | Packet::Subscribe(_)
+            | Packet::SubAck(_)
+            | Packet::Disconnect(_)
+            | Packet::PingReq
+            | Packet::PingResp => &[],
+        }
+    }
+
+    // returns the Remaining Length: the encoded size of everything after the fixed header,
+    // including the application payload
+    fn body_len(&self) -> usize {
+        match self {
+            Packet::Connect(connect) => connect.body_len(),
+            Packet::ConnAck(connack) => connack.body_len(),
+            Packet::Publish(publish) => publish.body_len(),
+            Packet::PubAck(response)
+            | Packet::PubRec(response)
+            | Packet::PubRel(response)
+            | Packet::PubComp(response) => response.body_len(),
+            Packet::Subscribe(subscribe) => subscribe.body_len(),
+            Packet::SubAck(suback) => suback.body_len(),
+            Packet::Disconnect(disconnect) => disconnect.body_len(),
+            Packet::PingReq | Packet::PingResp => 0,
+        }
+    }
+
+    // writes everything after the fixed header except the application payload
+    fn encode_body(&self, writer: &mut ByteWriter) -> Result<(), MqttError> {
+        match self {
+            Packet::Connect(connect) => connect.encode_body(writer),
+            Packet::ConnAck(connack) => connack.encode_body(writer),
+            Packet::Publish(publish) => publish.encode_body(writer),
+            Packet::PubAck(response)
+            | Packet::PubRec(response)
+            | Packet::PubRel(response)
+            | Packet::PubComp(response) => response.encode_body(writer),
+            Packet::Subscribe(subscribe) => subscribe.encode_body(writer),
+            Packet::SubAck(suback) => suback.encode_body(writer),
+            Packet::Disconnect(disconnect) => disconnect.encode_body(writer),
+            Packet::PingReq | Packet::PingResp => Ok(()),
+        }
+    }
+
+    // parses everything after the fixed header. only MQTT 5 is supported, so the
+    // packet type alone selects the parser.
+    fn decode_body(
+        header: &FixedHeader,
+        body: &'a [u8],
+        validation: Utf8Validation,
+    ) -> Result<Self, MqttError> {
+        let mut reader = ByteReader::with_validation(body, validation);
+
+        let packet = match header {
+            FixedHeader::Standard { packet_type } => match packet_type {
+                ControlPacketType::CONNECT => Packet::Connect(Connect::decode_body(&mut reader)?),
+                ControlPacketType::CONNACK => Packet::ConnAck(ConnAck::decode_body(&mut reader)?),
+                ControlPacketType::PUBACK => {
+                    Packet::PubAck(PublishResponse::decode_body(&mut reader)?)
+                }
+                ControlPacketType::PUBREC => {
+                    Packet::PubRec(PublishResponse::decode_body(&mut reader)?)
+                }
+                ControlPacketType::PUBREL => {
+                    Packet::PubRel(PublishResponse::decode_body(&mut reader)?)
+                }
+                ControlPacketType::PUBCOMP => {
+                    Packet::PubComp(PublishResponse::decode_body(&mut reader)?)
+                }
+                ControlPacketType::SUBSCRIBE => {
+                    Packet::Subscribe(Subscribe::decode_body(&mut reader)?)
+                }
+                ControlPacketType::SUBACK => Packet::SubAck(SubAck::decode_body(&mut reader)?),
+                ControlPacketType::PINGREQ => Packet::PingReq,
+                ControlPacketType::PINGRESP => Packet::PingResp,
+                ControlPacketType::DISCONNECT => {
+                    Packet::Disconnect(Disconnect::decode_body(&mut reader)?)
+                }
+                _ => return Err(MqttError::UnsupportedPacketType),
+            },
+            FixedHeader::Publish { .. } => {
+                Packet::Publish(Publish::decode_body(header, &mut reader)?)
+            }
+        };
+
+        // the body must account for the whole Remaining Length
+        if !reader.is_empty() {
+            return Err(MqttError::MalformedPacket);
+        }
+
+        Ok(packet)
+    }
+}
+
+impl<const N: usize> MqttEncode for Packet<'_, N> {
+    type Error = MqttError;
+
+    /// Encodes the complete packet, computing the Remaining Length
+    /// Returns the number of bytes written
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        self.encode_with_validation(buffer, Utf8Validation::Strict)
+    }
+
+    fn encoded_len(&self) -> usize {
+        packet_len(self.body_len())
+    }
+}
+
+/// Formatted on one line as the packet type and flags, followed by the packet's fields,
+/// e.g. `PUBACK packet_id=1 reason_code=0x00`
+impl<const N: usize> fmt::Display for Packet<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.fixed_header() {
+            Ok(header) => write!(f, "{header}")?,
+            Err(_) => write!(f, "{}", self.packet_type())?,
+        }
+
+        match self {
+            Packet::Connect(connect) => write!(f, " {connect}"),
+            Packet::ConnAck(connack) => write!(f, " {connack}"),
+            Packet::Publish(publish) => write!(f, " {publish}"),
+            Packet::PubAck(response)
+            | Packet::PubRec(response)
+            | Packet::PubRel(response)
+            | Packet::PubComp(response) => write!(f, " {response}"),
+            Packet::Subscribe(subscribe) => write!(f, " {subscribe}"),
+            Packet::SubAck(suback) => write!(f, " {suback}"),
+            Packet::Disconnect(disconnect) => write!(f, " {disconnect}"),
+            Packet::PingReq | Packet::PingResp => Ok(()),
+        }
+    }
+}
+
+impl<'a, const N: usize> MqttDecode<'a> for Packet<'a, N> {
+    type Error = MqttError;
+
+    /// Decodes a complete packet from the start of the buffer; any following packets are left untouched
+    /// Returns the packet and the number of bytes it occupied, including the fixed header
+    fn decode(buffer: &'a [u8]) -> Result<(Self, usize), Self::Error> {
+        // the largest packet the Remaining Length can describe is always accepted
+        Packet::decode_with_max_packet_size(buffer, usize::MAX)
+    }
+}
+
+// within a packet the Remaining Length bounds every field, so running out of bytes
+// means the packet is malformed rather than incomplete
+pub(crate) fn body_error(error: DataRepresentationError) -> MqttError {
+    match error {
+        DataRepresentationError::BufferUnderflow
+        | DataRepresentationError::IntegerMalformedBuffer
+        | DataRepresentationError::Utf8MalformedBuffer
+        | DataRepresentationError::BinaryMalformedBuffer => MqttError::MalformedPacket,
+        error => error.into(),
+    }
+}
+
+// reads a property block, validating its strings as strictly as the reader does
+pub(crate) fn read_properties<'a, const N: usize>(
+    reader: &mut ByteReader<'a>,
+) -> Result<Properties<'a, N>, MqttError> {
+    let (properties, consumed) =
+        Properties::decode_with_validation(reader.rest(), reader.validation())?;
+    reader.read_bytes(consumed).map_err(body_error)?;
+
+    Ok(properties)
+}
+
+// writes a property block, validating its strings as strictly as the writer does
+pub(crate) fn write_properties<const N: usize>(
+    writer: &mut ByteWriter,
+    properties: &Properties<'_, N>,
+) -> Result<(), MqttError> {
+    let validation = writer.validation();
+    writer.put_with(|target| properties.encode_with_validation(target, validation))
+}
+
+// splits the packet at the start of the buffer into its fixed header and body, rejecting it
+// as soon as the fixed header shows it is larger than `max_packet_size`.
+// returns the header, the body, and the length of the whole packet
+fn split_packet(
+    buffer: &[u8],
+    max_packet_size: usize,
+) -> Result<(FixedHeader, &[u8], usize), MqttError> {
+    let (header, remaining_length, header_len) = FixedHeader::decode(buffer)?;
+
+    let packet_len = header_len + remaining_length as usize;
+    if packet_len > max_packet_size {
+        return Err(MqttError::PacketTooLarge);
+    }
+
+    let body = buffer
+        .get(header_len..packet_len)
+        .ok_or(MqttError::IncompletePacket)?;
+
+    Ok((header, body, packet_len))
+}
+
+// decodes a complete packet of a single, fixed-flag type, parsing its body with `decode_body`.
+// returns the decoded value and the length of the whole packet
+pub(crate) fn decode_packet<'a, T>(
+    buffer: &'a [u8],
+    packet_type: ControlPacketType,
+    decode_body: impl FnOnce(&mut ByteReader<'a>) -> Result<T, MqttError>,
+) -> Result<(T, usize), MqttError> {
+    let (header, body, packet_len) = split_packet(buffer, usize::MAX)?;
+    if header != FixedHeader::new(packet_type)? {
+        return Err(MqttError::InvalidPacketType);
+    }
+
+    let mut reader = ByteReader::new(body);
+    let value = decode_body(&mut reader)?;
+
+    // the body must account for the whole Remaining Length
+    if !reader.is_empty() {
+        return Err(MqttError::MalformedPacket);
+    }
+
+    Ok((value, packet_len))
+}
+
+// returns the encoded size of a packet with a body of `body_len` bytes.
+// every fixed header is 1 byte plus the Remaining Length, which is at most 4 bytes
+pub(crate) fn packet_len(body_len: usize) -> usize {
+    let remaining_length_len = u32::try_from(body_len)
+        .ok()
+        .and_then(|len| VariableByteInt::new(len).ok())
+        .map(VariableByteInt::length)
+        .unwrap_or(4);
+
+    1 + remaining_length_len + body_len
+}
+
+// encodes a fixed header with a Remaining Length of `body_len`, followed by the body written by
+// `encode_body`: everything but the trailing `payload_len` bytes of application payload, which
+// the caller appends (or hands to the transport) itself. strings in the body are validated
+// as given.
+// returns the number of bytes written
+pub(crate) fn encode_head(
+    header: &FixedHeader,
+    body_len: usize,
+    payload_len: usize,
+    buffer: &mut [u8],
+    validation: Utf8Validation,
+    encode_body: impl FnOnce(&mut ByteWriter) -> Result<(), MqttError>,
+) -> Result<usize, MqttError> {
+    let remaining_length = u32::try_from(body_len).map_err(|_| MqttError::MalformedPacket)?;
+    let encoded_header = header.encode(remaining_length)?;
+
+    // check for room up front, so a packet is never partially written
+    let head_len = (encoded_header.as_bytes().len() + body_len).saturating_sub(payload_len);
+    let mut writer = ByteWriter::with_validation(
+        buffer
+            .get_mut(..head_len)
+            .ok_or(MqttError::BufferOverflow)?,
+        validation,
+    );
+
+    writer.put_bytes(encoded_header.as_bytes())?;
+    encode_body(&mut writer)?;
+
+    // a body shorter than body_len promised would leave the Remaining Length wrong
+    if writer.position() != head_len {
+        return Err(MqttError::MalformedPacket);
+    }
+
+    Ok(writer.position())
+}
+
+#[cfg(test)]
+mod test_packet_encode {
+    use super::*;
+    use crate::fixed_header::PINGREQ_PACKET;
+    use crate::properties::{Properties, Property};
+
+    #[test]
+    fn test_encode_pingreq() {
+        let mut buffer = [0xFF; 8];
+        let len = Packet::<0>::PingReq.encode(&mut buffer).unwrap();
+
+        assert_eq!(&buffer[..len], &PINGREQ_PACKET);
+    }
+
+    #[test]
+    fn test_encode_puback() {
+        let packet = Packet::<0>::PubAck(PublishResponse::new(0x1234).unwrap());
+
+        let mut buffer = [0; 16];
+        let len = packet.encode(&mut buffer).unwrap();
+
+        // a successful response with no properties omits the reason code
+        assert_eq!(&buffer[..len], &[0x40, 0x02, 0x12, 0x34]);
+    }
+
+    #[test]
+    fn test_encode_pubrel_with_properties() {
+        let mut properties = Properties::<1>::new();
+        properties.push(Property::ReasonString("gone")).unwrap();
+
+        let packet = Packet::PubRel(PublishResponse {
+            packet_identifier: 0x0001,
+            reason_code: 0x92, // packet identifier not found
+            properties,
+        });
+
+        let mut buffer = [0; 16];
+        let len = packet.encode(&mut buffer).unwrap();
+
+        assert_eq!(
+            &buffer[..len],
+            &[
+                0x62, 0x0B, // PUBREL, remaining length 11
+                0x00, 0x01, // packet identifier
+                0x92, // reason code
+                0x07, // property length
+                0x1F, 0x00, 0x04, b'g', b'o', b'n', b'e', // reason string
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_into_exact_buffer() {
+        let packet = Packet::<0>::PubAck(PublishResponse::new(1).unwrap());
+
+        let mut buffer = [0; 4];
+        assert_eq!(packet.encode(&mut buffer), Ok(4));
+        assert_eq!(
+            packet.encode(&mut buffer[..3]),
+            Err(MqttError::BufferOverflow)
+        );
+    }
+
+    #[test]
+    fn test_encode_over_max_packet_size() {
+        let packet = Packet::<0>::PubAck(PublishResponse::new(1).unwrap());
+
+        let mut buffer = [0; 16];
+        assert_eq!(
+            packet.encode_with_max_packet_size(&mut buffer, 3),
+            Err(MqttError::PacketTooLarge)
+        );
+        assert_eq!(packet.encode_with_max_packet_size(&mut buffer, 4), Ok(4));
+    }
+
+    #[test]
+    fn test_encoded_len_matches_encode() {
+        let mut properties = Properties::<2>::new();
+        properties.push(Property::ReasonString("gone")).unwrap();
+        properties
+            .push(Property::UserProperty("key", "value"))
+            .unwrap();
+
+        let packets = [
+            Packet::PingResp,
+            Packet::PubAck(PublishResponse::new(1).unwrap()),
+            Packet::PubRec(PublishResponse {
+                packet_identifier: 2,
+                reason_code: 0x80,
+                properties: Properties::new(),
+            }),
+            Packet::PubComp(PublishResponse {
+                packet_identifier: 3,
+                reason_code: 0x00,
+                properties,
+            }),
+        ];
+        let mut buffer = [0; 32];
+
+        for packet in packets {
+            assert_eq!(packet.encode(&mut buffer), Ok(packet.encoded_len()));
+        }
+    }
+
+    #[test]
+    fn test_body_len_matches_encode_body_for_every_type() {
+        use crate::fixed_header::QOS;
+
+        let mut subscribe = Subscribe::new(4).unwrap();
+        subscribe
+            .push(Subscription::new("a/#", QOS::ATLEASTONCE))
+            .unwrap();
+        let mut suback = SubAck::new(4).unwrap();
+        suback.push(SubscribeReasonCode::GrantedQoS1).unwrap();
+
+        let packets: [Packet<'_, 1>; 12] = [
+            Packet::Connect(Connect::new(ClientId::server_assigned())),
+            Packet::ConnAck(ConnAck::new(ConnectReasonCode::Success)),
+            Packet::Publish(Publish::new("a/b", b"payload")),
+            Packet::PubAck(PublishResponse::new(1).unwrap()),
+            Packet::PubRec(PublishResponse::new(2).unwrap()),
+            Packet::PubRel(PublishResponse::new(3).unwrap()),
+            Packet::PubComp(PublishResponse::new(4).unwrap()),
+            Packet::Subscribe(subscribe),
+            Packet::SubAck(suback),
+            Packet::Disconnect(Disconnect::new(DisconnectReasonCode::UnspecifiedError)),
+            Packet::PingReq,
+            Packet::PingResp,
+        ];
+        let mut buffer = [0; 64];
+
+        // encode_head rejects a body that disagrees with body_len, so every type must agree
+        for packet in packets {
+            assert_eq!(packet.encode(&mut buffer), Ok(packet.encoded_len()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_packet_encode_vectored {
+    use super::*;
+
+    #[test]
+    fn test_encode_vectored_without_payload() {
+        let packet = Packet::<0>::PubAck(PublishResponse::new(0x1234).unwrap());
+
+        let mut buffer = [0; 4];
+        let (head_len, payload) = packet.encode_vectored(&mut buffer).unwrap();
+
+        assert_eq!(&buffer[..head_len], &[0x40, 0x02, 0x12, 0x34]);
+        assert!(payload.is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_encode_io_slices() {
+        use std::io::Write;
+
+        let packet = Packet::<0>::PubAck(PublishResponse::new(0x1234).unwrap());
+
+        let mut buffer = [0; 4];
+        let slices = packet.encode_io_slices(&mut buffer).unwrap();
+
+        let mut written = Vec::new();
+        assert_eq!(written.write_vectored(&slices).unwrap(), 4);
+        assert_eq!(written, [0x40, 0x02, 0x12, 0x34]);
+    }
+}
+
+#[cfg(test)]
+mod test_packet_display {
+    use super::*;
+    use crate::properties::Property;
+
+    #[test]
+    fn test_display_without_fields() {
+        assert_eq!(format!("{}", Packet::<0>::PingReq), "PINGREQ");
+    }
+
+    #[test]
+    fn test_display_publish_response() {
+        let mut response = PublishResponse::<1>::new(5).unwrap();
+        response.reason_code = 0x92;
+        assert_eq!(
+            format!("{}", Packet::PubComp(response.clone())),
+            "PUBCOMP packet_id=5 reason_code=0x92"
+        );
+
+        response
+            .properties
+            .push(Property::ReasonString("not found"))
+            .unwrap();
+        assert_eq!(
+            format!("{}", Packet::PubComp(response)),
+            "PUBCOMP packet_id=5 reason_code=0x92 properties={ReasonString=\"not found\"}"
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_packet_decode {
+    use super::*;
+    use crate::properties::Property;
+
+    #[test]
+    fn test_decode_reversibility() {
+        let mut response = PublishResponse::<2>::new(0x0102).unwrap();
+        response.reason_code = 0x80; // unspecified error
+        response
+            .properties
+            .push(Property::UserProperty("key", "value"))
+            .unwrap();
+        let packet = Packet::PubRec(response);
+
+        let mut buffer = [0; 32];
+        let len = packet.encode(&mut buffer).unwrap();
+
+        assert_eq!(Packet::decode(&buffer[..len]), Ok((packet, len)));
+    }
+
+    #[test]
+    fn test_decode_back_to_back_packets() {
+        let buffer = [
+            0xC0, 0x00, // PINGREQ
+            0x70, 0x03, 0x00, 0x05, 0x92, // PUBCOMP, packet identifier 5, reason code 0x92
+            0xD0, 0x00, // PINGRESP
+        ];
+
+        let (first, first_len) = Packet::<0>::decode(&buffer).unwrap();
+        let (second, second_len) = Packet::<0>::decode(&buffer[first_len..]).unwrap();
+        let (third, _) = Packet::<0>::decode(&buffer[first_len + second_len..]).unwrap();
+
+        assert_eq!(first, Packet::PingReq);
+        assert_eq!(
+            second,
+            Packet::PubComp(PublishResponse {
+                packet_identifier: 5,
+                reason_code: 0x92,
+                properties: crate::properties::Properties::new(),
+            })
+        );
+        assert_eq!(third, Packet::PingResp);
+    }
+
+    #[test]
+    fn test_decode_incomplete() {
+        let buffer = [0x40, 0x02, 0x00]; // PUBACK missing the last byte of its identifier
+        assert_eq!(
+            Packet::<0>::decode(&buffer),
+            Err(MqttError::IncompletePacket)
+        );
+    }
+
+    #[test]
+    fn test_decode_trailing_bytes_in_body() {
+        let buffer = [0xC0, 0x01, 0x00]; // PINGREQ with a non-zero Remaining Length
+        assert_eq!(
+            Packet::<0>::decode(&buffer),
+            Err(MqttError::MalformedPacket)
+        );
+    }
+
+    #[test]
+    fn test_decode_over_max_packet_size() {
+        // only the fixed header has arrived, but it already declares a 131 byte packet
+        let buffer = [0x40, 0x80, 0x01];
+
+        assert_eq!(
+            Packet::<0>::decode_with_max_packet_size(&buffer, 128),
+            Err(MqttError::PacketTooLarge)
+        );
+        assert_eq!(
+            Packet::<0>::decode_with_max_packet_size(&buffer, 131),
+            Err(MqttError::IncompletePacket)
+        );
+    }
+
+    #[test]
+    fn test_decode_with_lenient_validation() {
+        let buffer = [
+            0x30, 0x09, // PUBLISH, QoS 0
+            0x00, 0x02, 0x61, 0x01, // topic "a\u{1}"
+            0x04, 0x03, 0x00, 0x01, 0x02, // Content Type "\u{2}"
+        ];
+
+        assert!(Packet::<1>::decode(&buffer).is_err());
+
+        let (packet, len) =
+            Packet::<1>::decode_with_validation(&buffer, Utf8Validation::Lenient).unwrap();
+        let Packet::Publish(publish) = packet else {
+            panic!("expected PUBLISH, got {packet:?}");
+        };
+        assert_eq!(len, buffer.len());
+        assert_eq!(publish.topic, "a\u{1}");
+        assert_eq!(
+            publish.properties.iter().next(),
+            Some(&Property::ContentType("\u{2}"))
+        );
+    }
+
+    #[test]
+    fn test_lenient_packets_round_trip() {
+        let publish = [
+            0x30, 0x09, // PUBLISH, QoS 0
+            0x00, 0x02, 0x61, 0x09, // topic "a\t"
+            0x04, 0x03, 0x00, 0x01, 0x02, // Content Type "\u{2}"
+        ];
+
+        let mut properties = Properties::new();
+        properties
+            .push(Property::UserProperty("key\t", "value"))
+            .unwrap();
+        let connect = Packet::<1>::Connect(
+            Connect::builder()
+                .client_id(ClientId::from_validated("id\t"))
+                .will(Will::new("status\t", b"offline"))
+                .username("user\u{7f}")
+                .properties(properties)
+                .build(),
+        );
+        let mut connect_buffer = [0; 64];
+        assert!(connect.encode(&mut connect_buffer).is_err());
+        let connect_len = connect
+            .encode_with_validation(&mut connect_buffer, Utf8Validation::Lenient)
+            .unwrap();
+
+        for encoded in [&publish[..], &connect_buffer[..connect_len]] {
+            assert!(Packet::<1>::decode(encoded).is_err());
+
+            let (packet, len) =
+                Packet::<1>::decode_with_validation(encoded, Utf8Validation::Lenient).unwrap();
+            assert_eq!(len, encoded.len());
+
+            let mut buffer = [0; 64];
+            assert!(packet.encode(&mut buffer).is_err());
+            assert_eq!(
+                packet.encode_with_validation(&mut buffer, Utf8Validation::Lenient),
+                Ok(len)
+            );
+            assert_eq!(&buffer[..len], encoded);
+        }
+    }
+
+    #[test]
+    fn test_decode_unsupported_packet_type() {
+        let buffer = [0xB0, 0x02, 0x00, 0x01]; // UNSUBACK
+        assert_eq!(
+            Packet::<0>::decode(&buffer),
+            Err(MqttError::UnsupportedPacketType)
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use crate::data_representation::MqttDecode;
+use crate::error::MqttError;
+use crate::fixed_header::{ControlPacketType, FixedHeader, PINGREQ_PACKET, PINGRESP_PACKET};
+
+/// A PINGREQ packet, sent by the client to keep the connection alive. It has no variable
+/// header or payload, so it always encodes to the same two bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PingReq;
+
+/// A PINGRESP packet, the server's reply to PINGREQ. Like PINGREQ, it always encodes
+/// to the same two bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PingResp;
+
+impl PingReq {
+    /// Returns the encoded packet
+    pub const fn encode(&self) -> [u8; 2] {
+        PINGREQ_PACKET
+    }
+}
+
+impl PingResp {
+    /// Returns the encoded packet
+    pub const fn encode(&self) -> [u8; 2] {
+        PINGRESP_PACKET
+    }
+}
+
+impl MqttDecode<'_> for PingReq {
+    type Error = MqttError;
+
+    /// Decodes a PINGREQ from the start of the buffer
+    /// Returns the packet and the number of bytes it occupied
+    fn decode(buffer: &[u8]) -> Result<(Self, usize), Self::Error> {
+        let len = decode_empty_packet(buffer, ControlPacketType::PINGREQ)?;
+        Ok((PingReq, len))
+    }
+}
+
+impl MqttDecode<'_> for PingResp {
+    type Error = MqttError;
+
+    /// Decodes a PINGRESP from the start of the buffer
+    /// Returns the packet and the number of bytes it occupied
+    fn decode(buffer: &[u8]) -> Result<(Self, usize), Self::Error> {
+        let len = decode_empty_packet(buffer, ControlPacketType::PINGRESP)?;
+        Ok((PingResp, len))
+    }
+}
+
+// checks the buffer starts with a packet of the given type and a Remaining Length of 0.
+// a non-zero length is rejected as soon as it is read, without waiting for the bytes it claims.
+// returns the length of the packet
+fn decode_empty_packet(buffer: &[u8], packet_type: ControlPacketType) -> Result<usize, MqttError> {
+    let (header, remaining_length, header_len) = FixedHeader::decode(buffer)?;
+    if header != FixedHeader::new(packet_type)? {
+        return Err(MqttError::InvalidPacketType);
+    }
+    if remaining_length != 0 {
+        return Err(MqttError::MalformedPacket);
+    }
+
+    Ok(header_len)
+}
+
+#[cfg(test)]
+mod test_ping {
+    use super::*;
+    use crate::data_representation::MqttEncode;
+    use crate::packet::Packet;
+
+    #[test]
+    fn test_encode_matches_packet() {
+        let mut buffer = [0; 2];
+
+        Packet::<0>::PingReq.encode(&mut buffer).unwrap();
+        assert_eq!(PingReq.encode(), buffer);
+
+        Packet::<0>::PingResp.encode(&mut buffer).unwrap();
+        assert_eq!(PingResp.encode(), buffer);
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(PingReq::decode(&[0xC0, 0x00, 0xD0]), Ok((PingReq, 2)));
+        assert_eq!(PingResp::decode(&PingResp.encode()), Ok((PingResp, 2)));
+    }
+
+    #[test]
+    fn test_decode_rejects_non_zero_remaining_length() {
+        // rejected before the claimed body arrives
+        assert_eq!(
+            PingReq::decode(&[0xC0, 0x02]),
+            Err(MqttError::MalformedPacket)
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_other_packet_types() {
+        assert_eq!(
+            PingResp::decode(&PingReq.encode()),
+            Err(MqttError::InvalidPacketType)
+        );
+        assert_eq!(
+            PingReq::decode(&[0xE0, 0x00]),
+            Err(MqttError::InvalidPacketType)
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_incomplete_header() {
+        assert_eq!(PingReq::decode(&[0xC0]), Err(MqttError::IncompletePacket));
+    }
+}
@@ -0,0 +1,606 @@
+use super::{
+    Missing, body_error, encode_head, packet_len, read_properties, split_packet, write_properties,
+};
+use crate::data_representation::{ByteReader, ByteWriter, MqttDecode, MqttEncode, Utf8Validation};
+use crate::error::MqttError;
+use crate::fixed_header::{FixedHeader, QOS};
+use crate::properties::{Properties, Property};
+use core::fmt;
+use core::num::NonZeroU16;
+
+/// A PUBLISH packet, carrying an application message to the topic named.
+/// Its QoS, DUP and RETAIN settings are encoded in the fixed header; the payload is
+/// borrowed, so it is only copied if the packet is encoded into a single buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Publish<'a, const N: usize> {
+    pub dup: bool, // whether this is a re-delivery; only allowed above QoS 0
+    pub qos: QOS,
+    pub retain: bool,
+    pub topic: &'a str, // may be empty only if a Topic Alias property stands in for it
+    pub packet_identifier: Option<u16>, // present exactly when the QoS is above 0
+    pub properties: Properties<'a, N>,
+    pub payload: &'a [u8],
+}
+
+impl<'a, const N: usize> Publish<'a, N> {
+    /// Creates a QoS 0, non-retained publish with no properties
+    pub const fn new(topic: &'a str, payload: &'a [u8]) -> Self {
+        Self {
+            dup: false,
+            qos: QOS::ATMOSTONCE,
+            retain: false,
+            topic,
+            packet_identifier: None,
+            properties: Properties::new(),
+            payload,
+        }
+    }
+
+    /// Starts building a QoS 0, non-retained publish to `topic`. Choosing QoS 1 or 2
+    /// requires a packet identifier before the packet can be built
+    pub const fn builder(topic: &'a str) -> PublishBuilder<'a, N, ()> {
+        PublishBuilder {
+            packet_identifier: (),
+            qos: QOS::ATMOSTONCE,
+            dup: false,
+            retain: false,
+            topic,
+            properties: Properties::new(),
+            payload: &[],
+        }
+    }
+
+    /// Returns the fixed header this packet is encoded with
+    pub const fn fixed_header(&self) -> Result<FixedHeader, MqttError> {
+        FixedHeader::new_publish(self.qos, self.dup, self.retain)
+    }
+
+    // returns the Remaining Length: the variable header followed by the payload
+    pub(crate) fn body_len(&self) -> usize {
+        let packet_identifier_len = if self.packet_identifier.is_some() {
+            2
+        } else {
+            0
+        };
+
+        2 + self.topic.len()
+            + packet_identifier_len
+            + self.properties.encoded_len()
+            + self.payload.len()
+    }
+
+    // writes the variable header; the payload is left to the caller, so vectored
+    // encoding can send it without a copy
+    pub(crate) fn encode_body(&self, writer: &mut ByteWriter) -> Result<(), MqttError> {
+        self.validate()?;
+
+        writer.put_utf8_string(self.topic)?;
+        if let Some(packet_identifier) = self.packet_identifier {
+            writer.put_u16(packet_identifier)?;
+        }
+        write_properties(writer, &self.properties)?;
+
+        Ok(())
+    }
+
+    // reads the variable header and payload, taking the QoS, DUP and RETAIN settings from
+    // the fixed header. the payload is whatever remains, borrowed rather than copied
+    pub(crate) fn decode_body(
+        header: &FixedHeader,
+        reader: &mut ByteReader<'a>,
+    ) -> Result<Self, MqttError> {
+        let FixedHeader::Publish {
+            qos, dup, retain, ..
+        } = *header
+        else {
+            return Err(MqttError::InvalidPacketType);
+        };
+
+        let topic = reader.read_utf8_str().map_err(body_error)?;
+        let packet_identifier = match qos {
+            QOS::ATMOSTONCE => None,
+            QOS::ATLEASTONCE | QOS::EXACTLYONCE => Some(reader.read_u16().map_err(body_error)?),
+        };
+        let properties = read_properties(reader)?;
+        let payload = reader.read_bytes(reader.rest().len()).map_err(body_error)?;
+
+        let publish = Self {
+            dup,
+            qos,
+            retain,
+            topic,
+            packet_identifier,
+            properties,
+            payload,
+        };
+        publish.validate()?;
+
+        Ok(publish)
+    }
+
+    // checks the fields agree with each other and with the QoS
+    fn validate(&self) -> Result<(), MqttError> {
+        // a packet identifier is required above QoS 0, and forbidden at it [MQTT-2.2.1-2]
+        match (self.qos, self.packet_identifier) {
+            (QOS::ATMOSTONCE, None) => {}
+            (QOS::ATLEASTONCE | QOS::EXACTLYONCE, Some(packet_identifier))
+                if packet_identifier != 0 => {}
+            _ => return Err(MqttError::InvalidPacketIdentifier),
+        }
+
+        let has_topic_alias = self
+            .properties
+            .iter()
+            .any(|property| matches!(property, Property::TopicAlias(_)));
+        if !is_valid_topic_name(self.topic, has_topic_alias) {
+            return Err(MqttError::InvalidTopicName);
+        }
+
+        Ok(())
+    }
+}
+
+// topic names may not contain wildcards [MQTT-3.3.2-2], and may only be empty
+// when a Topic Alias identifies the topic instead [MQTT-3.3.2-1]
+pub(super) fn is_valid_topic_name(topic: &str, has_topic_alias: bool) -> bool {
+    (!topic.is_empty() || has_topic_alias) && !topic.contains(['+', '#'])
+}
+
+impl<const N: usize> MqttEncode for Publish<'_, N> {
+    type Error = MqttError;
+
+    /// Encodes the complete PUBLISH packet, fixed header and payload included
+    /// Returns the number of bytes written
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        // check for room up front, so the payload always fits after the header bytes
+        let packet = buffer
+            .get_mut(..self.encoded_len())
+            .ok_or(MqttError::BufferOverflow)?;
+
+        let head_len = encode_head(
+            &self.fixed_header()?,
+            self.body_len(),
+            self.payload.len(),
+            packet,
+            Utf8Validation::Strict,
+            |writer| self.encode_body(writer),
+        )?;
+        packet
+            .get_mut(head_len..)
+            .ok_or(MqttError::BufferOverflow)?
+            .copy_from_slice(self.payload);
+
+        Ok(packet.len())
+    }
+
+    fn encoded_len(&self) -> usize {
+        packet_len(self.body_len())
+    }
+}
+
+impl<'a, const N: usize> MqttDecode<'a> for Publish<'a, N> {
+    type Error = MqttError;
+
+    /// Decodes a complete PUBLISH packet, fixed header included; the topic, properties and
+    /// payload all borrow from the buffer
+    /// Returns the packet and the number of bytes it occupied
+    fn decode(buffer: &'a [u8]) -> Result<(Self, usize), Self::Error> {
+        let (header, body, packet_len) = split_packet(buffer, usize::MAX)?;
+        let publish = Publish::decode_body(&header, &mut ByteReader::new(body))?;
+
+        Ok((publish, packet_len))
+    }
+}
+
+/// Formatted as the topic, any packet identifier and the payload size, followed by any
+/// properties; the payload itself is left out, as it is often large or binary
+impl<const N: usize> fmt::Display for Publish<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "topic={:?}", self.topic)?;
+
+        if let Some(packet_identifier) = self.packet_identifier {
+            write!(f, " packet_id={packet_identifier}")?;
+        }
+        if !self.properties.is_empty() {
+            write!(f, " properties={}", self.properties)?;
+        }
+
+        write!(f, " payload_len={}", self.payload.len())
+    }
+}
+
+/// Builds a `Publish`. At QoS 0 it can be built straight away; choosing QoS 1 or 2 moves it
+/// to a state where `build` is only available once a (non-zero) packet identifier is set:
+///
+/// ```compile_fail
+/// let publish = midge::packet::Publish::<0>::builder("a/b").at_least_once().build();
+/// ```
+///
+/// DUP may likewise only be set once there is a packet identifier. The payload defaults to
+/// empty, retain to false, and the properties to none.
+#[derive(Debug, Clone)]
+pub struct PublishBuilder<'a, const N: usize, P> {
+    packet_identifier: P,
+    qos: QOS,
+    dup: bool,
+    retain: bool,
+    topic: &'a str,
+    properties: Properties<'a, N>,
+    payload: &'a [u8],
+}
+
+impl<'a, const N: usize, P> PublishBuilder<'a, N, P> {
+    /// Sets the payload
+    pub fn payload(mut self, payload: &'a [u8]) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    /// Sets whether the message is retained by the server
+    pub fn retain(mut self, retain: bool) -> Self {
+        self.retain = retain;
+        self
+    }
+
+    /// Sets the properties
+    pub fn properties(mut self, properties: Properties<'a, N>) -> Self {
+        self.properties = properties;
+        self
+    }
+
+    // moves to `qos`, carrying every other setting over
+    fn with_qos<Q>(self, qos: QOS, packet_identifier: Q) -> PublishBuilder<'a, N, Q> {
+        PublishBuilder {
+            packet_identifier,
+            qos,
+            dup: self.dup,
+            retain: self.retain,
+            topic: self.topic,
+            properties: self.properties,
+            payload: self.payload,
+        }
+    }
+}
+
+impl<'a, const N: usize> PublishBuilder<'a, N, ()> {
+    /// Publishes at QoS 1, which requires a packet identifier
+    pub fn at_least_once(self) -> PublishBuilder<'a, N, Missing> {
+        self.with_qos(QOS::ATLEASTONCE, Missing)
+    }
+
+    /// Publishes at QoS 2, which requires a packet identifier
+    pub fn exactly_once(self) -> PublishBuilder<'a, N, Missing> {
+        self.with_qos(QOS::EXACTLYONCE, Missing)
+    }
+
+    /// Builds the QoS 0 publish
+    pub fn build(self) -> Publish<'a, N> {
+        Publish {
+            dup: false,
+            qos: self.qos,
+            retain: self.retain,
+            topic: self.topic,
+            packet_identifier: None,
+            properties: self.properties,
+            payload: self.payload,
+        }
+    }
+}
+
+impl<'a, const N: usize> PublishBuilder<'a, N, Missing> {
+    /// Sets the packet identifier the publish is acknowledged with
+    pub fn packet_identifier(
+        self,
+        packet_identifier: NonZeroU16,
+    ) -> PublishBuilder<'a, N, NonZeroU16> {
+        let qos = self.qos;
+        self.with_qos(qos, packet_identifier)
+    }
+}
+
+impl<'a, const N: usize> PublishBuilder<'a, N, NonZeroU16> {
+    /// Sets whether this is a re-delivery of an earlier attempt
+    pub fn dup(mut self, dup: bool) -> Self {
+        self.dup = dup;
+        self
+    }
+
+    /// Builds the QoS 1 or 2 publish
+    pub fn build(self) -> Publish<'a, N> {
+        Publish {
+            dup: self.dup,
+            qos: self.qos,
+            retain: self.retain,
+            topic: self.topic,
+            packet_identifier: Some(self.packet_identifier.get()),
+            properties: self.properties,
+            payload: self.payload,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_publish_builder {
+    use super::*;
+
+    #[test]
+    fn test_builder_defaults() {
+        let publish = Publish::<0>::builder("a/b").build();
+
+        assert_eq!(publish, Publish::new("a/b", &[]));
+    }
+
+    #[test]
+    fn test_builder_sets_every_field() {
+        let mut properties = Properties::new();
+        properties
+            .push(Property::MessageExpiryInterval(60))
+            .unwrap();
+
+        let publish = Publish::<1>::builder("t")
+            .payload(b"hi")
+            .retain(true)
+            .properties(properties.clone())
+            .exactly_once()
+            .packet_identifier(NonZeroU16::MIN)
+            .dup(true)
+            .build();
+
+        assert_eq!(
+            publish,
+            Publish {
+                dup: true,
+                qos: QOS::EXACTLYONCE,
+                retain: true,
+                topic: "t",
+                packet_identifier: Some(1),
+                properties,
+                payload: b"hi",
+            }
+        );
+    }
+
+    #[test]
+    fn test_built_packets_encode() {
+        let mut buffer = [0; 16];
+
+        let qos_0 = Publish::<0>::builder("t").build();
+        assert!(qos_0.encode(&mut buffer).is_ok());
+
+        let qos_1 = Publish::<0>::builder("t")
+            .at_least_once()
+            .packet_identifier(NonZeroU16::MIN)
+            .build();
+        assert!(qos_1.encode(&mut buffer).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod test_publish_encode {
+    use super::*;
+    use crate::packet::Packet;
+
+    #[test]
+    fn test_encode_qos_0() {
+        let publish = Publish::<0>::new("a/b", b"hi");
+
+        let mut buffer = [0; 16];
+        let len = publish.encode(&mut buffer).unwrap();
+
+        assert_eq!(len, publish.encoded_len());
+        assert_eq!(
+            &buffer[..len],
+            &[
+                0x30, 0x08, // PUBLISH at QoS 0, remaining length 8
+                0x00, 0x03, b'a', b'/', b'b', // topic name
+                0x00, // no properties
+                b'h', b'i', // payload
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_qos_1_with_properties() {
+        let mut publish = Publish::<1>::new("t", &[0xAA, 0xBB]);
+        publish.qos = QOS::ATLEASTONCE;
+        publish.dup = true;
+        publish.retain = true;
+        publish.packet_identifier = Some(0x0102);
+        publish
+            .properties
+            .push(Property::MessageExpiryInterval(60))
+            .unwrap();
+
+        let mut buffer = [0; 32];
+        let len = Packet::Publish(publish).encode(&mut buffer).unwrap();
+
+        assert_eq!(
+            &buffer[..len],
+            &[
+                0x3B, 0x0D, // PUBLISH with DUP, QoS 1 and RETAIN, remaining length 13
+                0x00, 0x01, b't', // topic name
+                0x01, 0x02, // packet identifier
+                0x05, 0x02, 0x00, 0x00, 0x00, 0x3C, // message expiry interval of 60 seconds
+                0xAA, 0xBB, // payload
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_vectored_leaves_payload() {
+        let payload = [0x55; 64];
+        let packet = Packet::<0>::Publish(Publish::new("t", &payload));
+
+        let mut buffer = [0; 8];
+        let (head_len, borrowed) = packet.encode_vectored(&mut buffer).unwrap();
+
+        assert_eq!(&buffer[..head_len], &[0x30, 0x44, 0x00, 0x01, b't', 0x00]);
+        assert!(core::ptr::eq(borrowed, &payload[..]));
+    }
+
+    #[test]
+    fn test_encode_rejects_mismatched_packet_identifier() {
+        let mut buffer = [0; 16];
+
+        let mut publish = Publish::<0>::new("t", &[]);
+        publish.packet_identifier = Some(1);
+        assert_eq!(
+            publish.encode(&mut buffer),
+            Err(MqttError::InvalidPacketIdentifier)
+        );
+
+        publish.qos = QOS::EXACTLYONCE;
+        publish.packet_identifier = None;
+        assert_eq!(
+            publish.encode(&mut buffer),
+            Err(MqttError::InvalidPacketIdentifier)
+        );
+
+        publish.packet_identifier = Some(0);
+        assert_eq!(
+            publish.encode(&mut buffer),
+            Err(MqttError::InvalidPacketIdentifier)
+        );
+    }
+
+    #[test]
+    fn test_encode_rejects_dup_at_qos_0() {
+        let mut publish = Publish::<0>::new("t", &[]);
+        publish.dup = true;
+
+        let mut buffer = [0; 16];
+        assert_eq!(publish.encode(&mut buffer), Err(MqttError::InvalidDupFlag));
+    }
+
+    #[test]
+    fn test_encode_rejects_invalid_topic_names() {
+        let mut buffer = [0; 16];
+
+        for topic in ["", "a/+", "a/#"] {
+            assert_eq!(
+                Publish::<0>::new(topic, &[]).encode(&mut buffer),
+                Err(MqttError::InvalidTopicName)
+            );
+        }
+    }
+
+    #[test]
+    fn test_encode_empty_topic_with_alias() {
+        let mut publish = Publish::<1>::new("", &[]);
+        publish.properties.push(Property::TopicAlias(3)).unwrap();
+
+        let mut buffer = [0; 16];
+        let len = publish.encode(&mut buffer).unwrap();
+
+        assert_eq!(
+            &buffer[..len],
+            &[0x30, 0x06, 0x00, 0x00, 0x03, 0x23, 0x00, 0x03]
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let mut publish = Publish::<0>::new("a/b", b"hello");
+        publish.qos = QOS::ATLEASTONCE;
+        publish.packet_identifier = Some(7);
+
+        assert_eq!(
+            format!("{}", Packet::Publish(publish)),
+            "PUBLISH qos=1 dup=false retain=false topic=\"a/b\" packet_id=7 payload_len=5"
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_publish_decode {
+    use super::*;
+    use crate::packet::Packet;
+
+    #[test]
+    fn test_decode_qos_0() {
+        let buffer = [0x31, 0x08, 0x00, 0x03, b'a', b'/', b'b', 0x00, b'h', b'i'];
+
+        let mut publish = Publish::<0>::new("a/b", b"hi");
+        publish.retain = true;
+
+        assert_eq!(Publish::decode(&buffer), Ok((publish, buffer.len())));
+    }
+
+    #[test]
+    fn test_decode_borrows_payload() {
+        let buffer = [0x32, 0x07, 0x00, 0x01, b't', 0x00, 0x09, 0x00, 0xAA];
+
+        let (publish, _) = Publish::<0>::decode(&buffer).unwrap();
+
+        assert_eq!(publish.packet_identifier, Some(9));
+        assert!(core::ptr::eq(publish.payload, &buffer[8..]));
+    }
+
+    #[test]
+    fn test_decode_reversibility() {
+        let mut publish = Publish::<2>::new("sensors/1", &[0x01, 0x02, 0x03]);
+        publish.qos = QOS::EXACTLYONCE;
+        publish.dup = true;
+        publish.packet_identifier = Some(0xBEEF);
+        publish
+            .properties
+            .push(Property::ContentType("application/octet-stream"))
+            .unwrap();
+        publish.properties.push(Property::TopicAlias(2)).unwrap();
+
+        let packet = Packet::Publish(publish);
+        let mut buffer = [0; 64];
+        let len = packet.encode(&mut buffer).unwrap();
+
+        assert_eq!(Packet::decode(&buffer[..len]), Ok((packet, len)));
+    }
+
+    #[test]
+    fn test_decode_empty_payload() {
+        let buffer = [0x30, 0x04, 0x00, 0x01, b't', 0x00];
+
+        let (publish, _) = Publish::<0>::decode(&buffer).unwrap();
+        assert!(publish.payload.is_empty());
+    }
+
+    #[test]
+    fn test_decode_rejects_zero_packet_identifier() {
+        let buffer = [0x32, 0x06, 0x00, 0x01, b't', 0x00, 0x00, 0x00];
+
+        assert_eq!(
+            Publish::<0>::decode(&buffer),
+            Err(MqttError::InvalidPacketIdentifier)
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_wildcard_topic() {
+        let buffer = [0x30, 0x04, 0x00, 0x01, b'#', 0x00];
+
+        assert_eq!(
+            Publish::<0>::decode(&buffer),
+            Err(MqttError::InvalidTopicName)
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_truncated_variable_header() {
+        // QoS 1, but the packet ends before the packet identifier
+        let buffer = [0x32, 0x03, 0x00, 0x01, b't'];
+
+        assert_eq!(
+            Publish::<0>::decode(&buffer),
+            Err(MqttError::MalformedPacket)
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_other_packet_types() {
+        assert_eq!(
+            Publish::<0>::decode(&[0xC0, 0x00]),
+            Err(MqttError::InvalidPacketType)
+        );
+    }
+}
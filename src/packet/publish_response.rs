@@ -0,0 +1,254 @@
+use super::{Missing, body_error, read_properties, write_properties};
+use crate::data_representation::{ByteReader, ByteWriter, MqttEncode};
+use crate::error::MqttError;
+use crate::properties::Properties;
+use core::fmt;
+use core::num::NonZeroU16;
+
+// the reason code for a successful response, which may be omitted on the wire
+const SUCCESS: u8 = 0x00;
+
+/// The body shared by PUBACK, PUBREC, PUBREL and PUBCOMP: the packet identifier of the
+/// PUBLISH (or PUBREL) being acknowledged, a reason code, and properties.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PublishResponse<'a, const N: usize> {
+    pub packet_identifier: u16,
+    pub reason_code: u8,
+    pub properties: Properties<'a, N>,
+}
+
+impl<'a, const N: usize> PublishResponse<'a, N> {
+    /// Creates a successful response with no properties
+    pub fn new(packet_identifier: u16) -> Result<Self, MqttError> {
+        if packet_identifier == 0 {
+            return Err(MqttError::InvalidPacketIdentifier);
+        }
+
+        Ok(Self {
+            packet_identifier,
+            reason_code: SUCCESS,
+            properties: Properties::new(),
+        })
+    }
+
+    /// Starts building a response, with the packet identifier still to be set
+    pub fn builder() -> PublishResponseBuilder<'a, N, Missing> {
+        PublishResponseBuilder {
+            packet_identifier: Missing,
+            reason_code: SUCCESS,
+            properties: Properties::new(),
+        }
+    }
+
+    // returns the encoded size of the variable header, omitting fields as encode_body does
+    pub(crate) fn body_len(&self) -> usize {
+        if !self.properties.is_empty() {
+            3 + self.properties.encoded_len()
+        } else if self.reason_code != SUCCESS {
+            3
+        } else {
+            2
+        }
+    }
+
+    // writes the variable header; these packets have no payload.
+    // trailing fields are omitted where the spec allows: the reason code when it is Success
+    // and there are no properties, and the property length when there are no properties.
+    pub(crate) fn encode_body(&self, writer: &mut ByteWriter) -> Result<(), MqttError> {
+        if self.packet_identifier == 0 {
+            return Err(MqttError::InvalidPacketIdentifier);
+        }
+
+        writer.put_u16(self.packet_identifier)?;
+
+        if self.properties.is_empty() {
+            if self.reason_code != SUCCESS {
+                writer.put_u8(self.reason_code)?;
+            }
+        } else {
+            writer.put_u8(self.reason_code)?;
+            write_properties(writer, &self.properties)?;
+        }
+
+        Ok(())
+    }
+
+    // reads the variable header, applying the defaults for omitted trailing fields
+    pub(crate) fn decode_body(reader: &mut ByteReader<'a>) -> Result<Self, MqttError> {
+        let packet_identifier = reader.read_u16().map_err(body_error)?;
+        if packet_identifier == 0 {
+            return Err(MqttError::InvalidPacketIdentifier);
+        }
+
+        let reason_code = if reader.is_empty() {
+            SUCCESS
+        } else {
+            reader.read_u8().map_err(body_error)?
+        };
+
+        let properties = if reader.is_empty() {
+            Properties::new()
+        } else {
+            read_properties(reader)?
+        };
+
+        Ok(Self {
+            packet_identifier,
+            reason_code,
+            properties,
+        })
+    }
+}
+
+/// Formatted as the packet identifier and reason code, followed by any properties
+impl<const N: usize> fmt::Display for PublishResponse<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "packet_id={} reason_code={:#04x}",
+            self.packet_identifier, self.reason_code
+        )?;
+
+        if !self.properties.is_empty() {
+            write!(f, " properties={}", self.properties)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds a `PublishResponse`. `build` is only available once the (non-zero) packet
+/// identifier is set, so a response can never be built without one:
+///
+/// ```compile_fail
+/// let response = midge::packet::PublishResponse::<0>::builder().build();
+/// ```
+///
+/// The reason code defaults to Success, and the properties to none.
+#[derive(Debug, Clone)]
+pub struct PublishResponseBuilder<'a, const N: usize, P> {
+    packet_identifier: P,
+    reason_code: u8,
+    properties: Properties<'a, N>,
+}
+
+impl<'a, const N: usize, P> PublishResponseBuilder<'a, N, P> {
+    /// Sets the packet identifier of the packet being acknowledged
+    pub fn packet_identifier(
+        self,
+        packet_identifier: NonZeroU16,
+    ) -> PublishResponseBuilder<'a, N, NonZeroU16> {
+        PublishResponseBuilder {
+            packet_identifier,
+            reason_code: self.reason_code,
+            properties: self.properties,
+        }
+    }
+
+    /// Sets the reason code
+    pub fn reason_code(mut self, reason_code: u8) -> Self {
+        self.reason_code = reason_code;
+        self
+    }
+
+    /// Sets the properties
+    pub fn properties(mut self, properties: Properties<'a, N>) -> Self {
+        self.properties = properties;
+        self
+    }
+}
+
+impl<'a, const N: usize> PublishResponseBuilder<'a, N, NonZeroU16> {
+    /// Builds the response
+    pub fn build(self) -> PublishResponse<'a, N> {
+        PublishResponse {
+            packet_identifier: self.packet_identifier.get(),
+            reason_code: self.reason_code,
+            properties: self.properties,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_publish_response {
+    use super::*;
+
+    fn encode<const N: usize>(response: &PublishResponse<'_, N>) -> ([u8; 16], usize) {
+        let mut buffer = [0; 16];
+        let mut writer = ByteWriter::new(&mut buffer);
+        response.encode_body(&mut writer).unwrap();
+        let len = writer.position();
+
+        (buffer, len)
+    }
+
+    #[test]
+    fn test_new_rejects_zero_packet_identifier() {
+        assert_eq!(
+            PublishResponse::<0>::new(0),
+            Err(MqttError::InvalidPacketIdentifier)
+        );
+    }
+
+    #[test]
+    fn test_encode_keeps_reason_code_without_properties() {
+        let response = PublishResponse::<0> {
+            packet_identifier: 7,
+            reason_code: 0x10, // no matching subscribers
+            properties: Properties::new(),
+        };
+
+        let (buffer, len) = encode(&response);
+        assert_eq!(&buffer[..len], &[0x00, 0x07, 0x10]);
+    }
+
+    #[test]
+    fn test_builder_defaults() {
+        let response = PublishResponse::<0>::builder()
+            .packet_identifier(NonZeroU16::MIN)
+            .build();
+
+        assert_eq!(response, PublishResponse::new(1).unwrap());
+    }
+
+    #[test]
+    fn test_builder_sets_every_field() {
+        let mut properties = Properties::<1>::new();
+        properties
+            .push(crate::properties::Property::ReasonString("quota"))
+            .unwrap();
+
+        let response = PublishResponse::builder()
+            .reason_code(0x97) // quota exceeded
+            .properties(properties.clone())
+            .packet_identifier(NonZeroU16::new(9).unwrap())
+            .build();
+
+        assert_eq!(
+            response,
+            PublishResponse {
+                packet_identifier: 9,
+                reason_code: 0x97,
+                properties,
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_applies_defaults() {
+        let mut reader = ByteReader::new(&[0x00, 0x07]);
+        let response = PublishResponse::<0>::decode_body(&mut reader).unwrap();
+
+        assert_eq!(response, PublishResponse::new(7).unwrap());
+    }
+
+    #[test]
+    fn test_decode_rejects_zero_packet_identifier() {
+        let mut reader = ByteReader::new(&[0x00, 0x00]);
+        assert_eq!(
+            PublishResponse::<0>::decode_body(&mut reader),
+            Err(MqttError::InvalidPacketIdentifier)
+        );
+    }
+}
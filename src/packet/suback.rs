@@ -0,0 +1,319 @@
+use super::{
+    body_error, decode_packet, encode_head, packet_len, read_properties, write_properties,
+};
+use crate::data_representation::{ByteReader, ByteWriter, MqttDecode, MqttEncode, Utf8Validation};
+use crate::error::MqttError;
+use crate::fixed_header::{ControlPacketType, FixedHeader, QOS};
+use crate::properties::{Properties, Property};
+use core::fmt;
+
+/// The outcome of one subscription in a SUBSCRIBE, reported in SUBACK in the same order
+/// as the topic filters. Codes of 0x80 and above mean the subscription was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum SubscribeReasonCode {
+    GrantedQoS0 = 0x00,
+    GrantedQoS1 = 0x01,
+    GrantedQoS2 = 0x02,
+    UnspecifiedError = 0x80,
+    ImplementationSpecificError = 0x83,
+    NotAuthorized = 0x87,
+    TopicFilterInvalid = 0x8F,
+    PacketIdentifierInUse = 0x91,
+    QuotaExceeded = 0x97,
+    SharedSubscriptionsNotSupported = 0x9E,
+    SubscriptionIdentifiersNotSupported = 0xA1,
+    WildcardSubscriptionsNotSupported = 0xA2,
+}
+
+impl SubscribeReasonCode {
+    /// Returns the reason code granting the subscription at the given QoS
+    pub const fn granted(qos: QOS) -> Self {
+        match qos {
+            QOS::ATMOSTONCE => SubscribeReasonCode::GrantedQoS0,
+            QOS::ATLEASTONCE => SubscribeReasonCode::GrantedQoS1,
+            QOS::EXACTLYONCE => SubscribeReasonCode::GrantedQoS2,
+        }
+    }
+
+    /// Returns the QoS the subscription was granted at, or None if it was refused
+    pub const fn granted_qos(&self) -> Option<QOS> {
+        match self {
+            SubscribeReasonCode::GrantedQoS0 => Some(QOS::ATMOSTONCE),
+            SubscribeReasonCode::GrantedQoS1 => Some(QOS::ATLEASTONCE),
+            SubscribeReasonCode::GrantedQoS2 => Some(QOS::EXACTLYONCE),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<u8> for SubscribeReasonCode {
+    type Error = MqttError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(SubscribeReasonCode::GrantedQoS0),
+            0x01 => Ok(SubscribeReasonCode::GrantedQoS1),
+            0x02 => Ok(SubscribeReasonCode::GrantedQoS2),
+            0x80 => Ok(SubscribeReasonCode::UnspecifiedError),
+            0x83 => Ok(SubscribeReasonCode::ImplementationSpecificError),
+            0x87 => Ok(SubscribeReasonCode::NotAuthorized),
+            0x8F => Ok(SubscribeReasonCode::TopicFilterInvalid),
+            0x91 => Ok(SubscribeReasonCode::PacketIdentifierInUse),
+            0x97 => Ok(SubscribeReasonCode::QuotaExceeded),
+            0x9E => Ok(SubscribeReasonCode::SharedSubscriptionsNotSupported),
+            0xA1 => Ok(SubscribeReasonCode::SubscriptionIdentifiersNotSupported),
+            0xA2 => Ok(SubscribeReasonCode::WildcardSubscriptionsNotSupported),
+            _ => Err(MqttError::InvalidReasonCode),
+        }
+    }
+}
+
+/// A SUBACK packet: the server's response to SUBSCRIBE, with one reason code per topic
+/// filter. Both the properties and the reason codes hold up to N entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SubAck<'a, const N: usize> {
+    pub packet_identifier: u16,
+    pub properties: Properties<'a, N>,
+    reason_codes: [SubscribeReasonCode; N],
+    len: usize,
+}
+
+impl<'a, const N: usize> SubAck<'a, N> {
+    /// Creates a response with no properties; a reason code must be pushed for each
+    /// subscription before it is encoded
+    pub fn new(packet_identifier: u16) -> Result<Self, MqttError> {
+        if packet_identifier == 0 {
+            return Err(MqttError::InvalidPacketIdentifier);
+        }
+
+        Ok(Self {
+            packet_identifier,
+            properties: Properties::new(),
+            reason_codes: [SubscribeReasonCode::UnspecifiedError; N],
+            len: 0,
+        })
+    }
+
+    /// Appends the reason code for the next subscription, if space allows
+    pub fn push(&mut self, reason_code: SubscribeReasonCode) -> Result<(), MqttError> {
+        let slot = self
+            .reason_codes
+            .get_mut(self.len)
+            .ok_or(MqttError::SubscriptionsCapacityExceeded)?;
+        *slot = reason_code;
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Returns the reason codes, in the order of the topic filters they answer
+    pub fn reason_codes(&self) -> &[SubscribeReasonCode] {
+        self.reason_codes.get(..self.len).unwrap_or_default()
+    }
+
+    // returns the encoded size of the variable header and payload
+    pub(crate) fn body_len(&self) -> usize {
+        2 + self.properties.encoded_len() + self.len
+    }
+
+    // writes the packet identifier and properties, then one reason code byte per subscription
+    pub(crate) fn encode_body(&self, writer: &mut ByteWriter) -> Result<(), MqttError> {
+        if self.packet_identifier == 0 {
+            return Err(MqttError::InvalidPacketIdentifier);
+        }
+        // every SUBSCRIBE has at least one topic filter to answer [MQTT-3.9.3-1]
+        if self.len == 0 {
+            return Err(MqttError::EmptySubscriptions);
+        }
+        if !has_valid_properties(&self.properties) {
+            return Err(MqttError::InvalidPropertyIdentifier);
+        }
+
+        writer.put_u16(self.packet_identifier)?;
+        write_properties(writer, &self.properties)?;
+        for reason_code in self.reason_codes() {
+            writer.put_u8(*reason_code as u8)?;
+        }
+
+        Ok(())
+    }
+
+    // reads the variable header, then reason codes until the body is exhausted
+    pub(crate) fn decode_body(reader: &mut ByteReader<'a>) -> Result<Self, MqttError> {
+        let mut suback = Self::new(reader.read_u16().map_err(body_error)?)?;
+
+        suback.properties = read_properties(reader)?;
+        if !has_valid_properties(&suback.properties) {
+            return Err(MqttError::MalformedProperties);
+        }
+
+        while !reader.is_empty() {
+            let reason_code = SubscribeReasonCode::try_from(reader.read_u8().map_err(body_error)?)
+                .map_err(|_| MqttError::MalformedPacket)?;
+            suback.push(reason_code)?;
+        }
+
+        if suback.len == 0 {
+            return Err(MqttError::MalformedPacket);
+        }
+
+        Ok(suback)
+    }
+}
+
+// SUBACK may carry a Reason String, and any User Properties
+fn has_valid_properties<const N: usize>(properties: &Properties<'_, N>) -> bool {
+    properties.iter().all(|property| {
+        matches!(
+            property,
+            Property::ReasonString(_) | Property::UserProperty(_, _)
+        )
+    })
+}
+
+impl<const N: usize> MqttEncode for SubAck<'_, N> {
+    type Error = MqttError;
+
+    /// Encodes the complete SUBACK packet, fixed header included
+    /// Returns the number of bytes written
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        let header = FixedHeader::new(ControlPacketType::SUBACK)?;
+        encode_head(
+            &header,
+            self.body_len(),
+            0,
+            buffer,
+            Utf8Validation::Strict,
+            |writer| self.encode_body(writer),
+        )
+    }
+
+    fn encoded_len(&self) -> usize {
+        packet_len(self.body_len())
+    }
+}
+
+impl<'a, const N: usize> MqttDecode<'a> for SubAck<'a, N> {
+    type Error = MqttError;
+
+    /// Decodes a complete SUBACK packet, fixed header included
+    /// Returns the packet and the number of bytes it occupied
+    fn decode(buffer: &'a [u8]) -> Result<(Self, usize), Self::Error> {
+        decode_packet(buffer, ControlPacketType::SUBACK, SubAck::decode_body)
+    }
+}
+
+/// Formatted as the packet identifier and reason codes, followed by any properties
+impl<const N: usize> fmt::Display for SubAck<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "packet_id={} reason_codes={:?}",
+            self.packet_identifier,
+            self.reason_codes()
+        )?;
+
+        if !self.properties.is_empty() {
+            write!(f, " properties={}", self.properties)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_suback {
+    use super::*;
+    use crate::packet::Packet;
+
+    #[test]
+    fn test_reason_code_reversibility() {
+        for value in 0..=u8::MAX {
+            if let Ok(reason_code) = SubscribeReasonCode::try_from(value) {
+                assert_eq!(reason_code as u8, value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_granted_qos() {
+        for qos in [QOS::ATMOSTONCE, QOS::ATLEASTONCE, QOS::EXACTLYONCE] {
+            assert_eq!(SubscribeReasonCode::granted(qos).granted_qos(), Some(qos));
+        }
+        assert_eq!(SubscribeReasonCode::NotAuthorized.granted_qos(), None);
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut suback = SubAck::<2>::new(10).unwrap();
+        suback.push(SubscribeReasonCode::GrantedQoS1).unwrap();
+        suback
+            .push(SubscribeReasonCode::WildcardSubscriptionsNotSupported)
+            .unwrap();
+
+        let mut buffer = [0; 16];
+        let len = suback.encode(&mut buffer).unwrap();
+
+        assert_eq!(len, suback.encoded_len());
+        assert_eq!(&buffer[..len], &[0x90, 0x05, 0x00, 0x0A, 0x00, 0x01, 0xA2]);
+    }
+
+    #[test]
+    fn test_decode_reversibility() {
+        let mut suback = SubAck::<3>::new(0x1234).unwrap();
+        suback
+            .properties
+            .push(Property::ReasonString("partial"))
+            .unwrap();
+        suback.push(SubscribeReasonCode::GrantedQoS2).unwrap();
+        suback.push(SubscribeReasonCode::GrantedQoS0).unwrap();
+        suback.push(SubscribeReasonCode::QuotaExceeded).unwrap();
+
+        let packet = Packet::SubAck(suback);
+        let mut buffer = [0; 32];
+        let len = packet.encode(&mut buffer).unwrap();
+
+        assert_eq!(Packet::decode(&buffer[..len]), Ok((packet, len)));
+    }
+
+    #[test]
+    fn test_encode_rejects_empty_reason_codes() {
+        let suback = SubAck::<1>::new(1).unwrap();
+
+        let mut buffer = [0; 16];
+        assert_eq!(
+            suback.encode(&mut buffer),
+            Err(MqttError::EmptySubscriptions)
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_unknown_reason_code() {
+        assert_eq!(
+            SubAck::<1>::decode(&[0x90, 0x04, 0x00, 0x01, 0x00, 0x03]),
+            Err(MqttError::MalformedPacket)
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_more_reason_codes_than_capacity() {
+        assert_eq!(
+            SubAck::<1>::decode(&[0x90, 0x05, 0x00, 0x01, 0x00, 0x00, 0x01]),
+            Err(MqttError::SubscriptionsCapacityExceeded)
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let mut suback = SubAck::<1>::new(4).unwrap();
+        suback.push(SubscribeReasonCode::GrantedQoS1).unwrap();
+
+        assert_eq!(
+            format!("{}", Packet::SubAck(suback)),
+            "SUBACK packet_id=4 reason_codes=[GrantedQoS1]"
+        );
+    }
+}
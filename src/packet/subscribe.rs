@@ -0,0 +1,548 @@
+use super::{
+    body_error, decode_packet, encode_head, packet_len, read_properties, write_properties,
+};
+use crate::data_representation::{ByteReader, ByteWriter, MqttDecode, MqttEncode, Utf8Validation};
+use crate::error::MqttError;
+use crate::fixed_header::{ControlPacketType, FixedHeader, QOS};
+use crate::properties::{Properties, Property};
+use core::fmt;
+
+/// Whether the server sends retained messages when a subscription is made
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum RetainHandling {
+    #[default]
+    SendOnSubscribe = 0,
+    SendOnNewSubscription = 1, // only if the subscription did not already exist
+    DoNotSend = 2,
+}
+
+/// The Subscription Options byte sent with each topic filter in SUBSCRIBE.
+/// Bits 7 and 6 are reserved, and always 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SubscriptionOptions {
+    pub maximum_qos: QOS, // the highest QoS the server may forward messages at
+    pub no_local: bool,   // whether to skip messages this client published itself
+    pub retain_as_published: bool, // whether forwarded messages keep their RETAIN flag
+    pub retain_handling: RetainHandling,
+}
+
+impl SubscriptionOptions {
+    // bit positions within the options byte
+    const NO_LOCAL: u8 = 0x04;
+    const RETAIN_AS_PUBLISHED: u8 = 0x08;
+    const RETAIN_HANDLING_SHIFT: u8 = 4;
+    const RESERVED: u8 = 0xC0;
+
+    /// Creates options with the given maximum QoS, and the defaults for the rest:
+    /// local messages are delivered, RETAIN is cleared, and retained messages are sent
+    pub const fn new(maximum_qos: QOS) -> Self {
+        Self {
+            maximum_qos,
+            no_local: false,
+            retain_as_published: false,
+            retain_handling: RetainHandling::SendOnSubscribe,
+        }
+    }
+
+    /// Returns the options byte
+    pub const fn to_byte(&self) -> u8 {
+        let mut options =
+            self.maximum_qos as u8 | (self.retain_handling as u8) << Self::RETAIN_HANDLING_SHIFT;
+
+        if self.no_local {
+            options |= Self::NO_LOCAL;
+        }
+        if self.retain_as_published {
+            options |= Self::RETAIN_AS_PUBLISHED;
+        }
+
+        options
+    }
+
+    /// Parses an options byte, rejecting it as malformed if a reserved bit is set,
+    /// or the QoS or Retain Handling is 3
+    pub const fn from_byte(options: u8) -> Result<Self, MqttError> {
+        // the reserved bits must be 0 [MQTT-3.8.3-5]
+        if options & Self::RESERVED != 0 {
+            return Err(MqttError::MalformedPacket);
+        }
+
+        let maximum_qos = match options & 0x03 {
+            0 => QOS::ATMOSTONCE,
+            1 => QOS::ATLEASTONCE,
+            2 => QOS::EXACTLYONCE,
+            _ => return Err(MqttError::MalformedPacket),
+        };
+        let retain_handling = match (options >> Self::RETAIN_HANDLING_SHIFT) & 0x03 {
+            0 => RetainHandling::SendOnSubscribe,
+            1 => RetainHandling::SendOnNewSubscription,
+            2 => RetainHandling::DoNotSend,
+            _ => return Err(MqttError::MalformedPacket),
+        };
+
+        Ok(Self {
+            maximum_qos,
+            no_local: options & Self::NO_LOCAL != 0,
+            retain_as_published: options & Self::RETAIN_AS_PUBLISHED != 0,
+            retain_handling,
+        })
+    }
+}
+
+impl Default for SubscriptionOptions {
+    fn default() -> Self {
+        Self::new(QOS::ATMOSTONCE)
+    }
+}
+
+impl From<SubscriptionOptions> for u8 {
+    fn from(options: SubscriptionOptions) -> Self {
+        options.to_byte()
+    }
+}
+
+impl TryFrom<u8> for SubscriptionOptions {
+    type Error = MqttError;
+
+    fn try_from(options: u8) -> Result<Self, Self::Error> {
+        Self::from_byte(options)
+    }
+}
+
+/// A topic filter, and the options to subscribe to it with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Subscription<'a> {
+    pub topic_filter: &'a str,
+    pub options: SubscriptionOptions,
+}
+
+impl<'a> Subscription<'a> {
+    /// Creates a subscription to the topic filter, with the given maximum QoS
+    /// and default options otherwise
+    pub const fn new(topic_filter: &'a str, maximum_qos: QOS) -> Self {
+        Self {
+            topic_filter,
+            options: SubscriptionOptions::new(maximum_qos),
+        }
+    }
+}
+
+/// A SUBSCRIBE packet: a request for messages published to one or more topic filters.
+/// Both the properties and the subscriptions hold up to N entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Subscribe<'a, const N: usize> {
+    pub packet_identifier: u16,
+    pub properties: Properties<'a, N>,
+    subscriptions: [Option<Subscription<'a>>; N],
+    len: usize,
+}
+
+impl<'a, const N: usize> Subscribe<'a, N> {
+    /// Creates a request with no properties; at least one subscription must be
+    /// pushed before it is encoded
+    pub fn new(packet_identifier: u16) -> Result<Self, MqttError> {
+        if packet_identifier == 0 {
+            return Err(MqttError::InvalidPacketIdentifier);
+        }
+
+        Ok(Self {
+            packet_identifier,
+            properties: Properties::new(),
+            subscriptions: [None; N],
+            len: 0,
+        })
+    }
+
+    /// Appends a subscription, if space allows
+    pub fn push(&mut self, subscription: Subscription<'a>) -> Result<(), MqttError> {
+        let slot = self
+            .subscriptions
+            .get_mut(self.len)
+            .ok_or(MqttError::SubscriptionsCapacityExceeded)?;
+        *slot = Some(subscription);
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Iterates over the subscriptions, in the order they were added
+    pub fn subscriptions(&self) -> impl Iterator<Item = &Subscription<'a>> {
+        self.subscriptions.iter().flatten()
+    }
+
+    /// Returns the Subscription Identifier, if one is set in the properties
+    pub fn subscription_identifier(&self) -> Option<u32> {
+        self.properties.iter().find_map(|property| match property {
+            Property::SubscriptionIdentifier(identifier) => Some(*identifier),
+            _ => None,
+        })
+    }
+
+    // returns the encoded size of the variable header and payload
+    pub(crate) fn body_len(&self) -> usize {
+        let subscriptions_len: usize = self
+            .subscriptions()
+            .map(|subscription| 2 + subscription.topic_filter.len() + 1)
+            .sum();
+
+        2 + self.properties.encoded_len() + subscriptions_len
+    }
+
+    // writes the packet identifier and properties, then each topic filter and its options
+    pub(crate) fn encode_body(&self, writer: &mut ByteWriter) -> Result<(), MqttError> {
+        if self.packet_identifier == 0 {
+            return Err(MqttError::InvalidPacketIdentifier);
+        }
+        // a SUBSCRIBE without any subscriptions is a protocol error [MQTT-3.8.3-2]
+        if self.len == 0 {
+            return Err(MqttError::EmptySubscriptions);
+        }
+        if !has_valid_properties(&self.properties) {
+            return Err(MqttError::InvalidPropertyIdentifier);
+        }
+
+        writer.put_u16(self.packet_identifier)?;
+        write_properties(writer, &self.properties)?;
+
+        for subscription in self.subscriptions() {
+            if !is_valid_topic_filter(subscription.topic_filter) {
+                return Err(MqttError::InvalidTopicFilter);
+            }
+
+            writer.put_utf8_string(subscription.topic_filter)?;
+            writer.put_u8(subscription.options.to_byte())?;
+        }
+
+        Ok(())
+    }
+
+    // reads the variable header, then subscriptions until the body is exhausted
+    pub(crate) fn decode_body(reader: &mut ByteReader<'a>) -> Result<Self, MqttError> {
+        let mut subscribe = Self::new(reader.read_u16().map_err(body_error)?)?;
+
+        subscribe.properties = read_properties(reader)?;
+        if !has_valid_properties(&subscribe.properties) {
+            return Err(MqttError::MalformedProperties);
+        }
+
+        while !reader.is_empty() {
+            let topic_filter = reader.read_utf8_str().map_err(body_error)?;
+            if !is_valid_topic_filter(topic_filter) {
+                return Err(MqttError::InvalidTopicFilter);
+            }
+            let options = SubscriptionOptions::from_byte(reader.read_u8().map_err(body_error)?)?;
+
+            subscribe.push(Subscription {
+                topic_filter,
+                options,
+            })?;
+        }
+
+        if subscribe.len == 0 {
+            return Err(MqttError::MalformedPacket);
+        }
+
+        Ok(subscribe)
+    }
+}
+
+// SUBSCRIBE may carry one non-zero Subscription Identifier, and any User Properties
+fn has_valid_properties<const N: usize>(properties: &Properties<'_, N>) -> bool {
+    let mut subscription_identifiers = 0;
+
+    properties.iter().all(|property| match property {
+        Property::SubscriptionIdentifier(identifier) => {
+            subscription_identifiers += 1;
+            *identifier != 0 && subscription_identifiers == 1
+        }
+        Property::UserProperty(_, _) => true,
+        _ => false,
+    })
+}
+
+// topic filters are non-empty; a multi-level wildcard must be the whole of the last level,
+// and a single-level wildcard the whole of its level [MQTT-4.7.1-1] [MQTT-4.7.1-2]
+fn is_valid_topic_filter(topic_filter: &str) -> bool {
+    if topic_filter.is_empty() {
+        return false;
+    }
+
+    let mut levels = topic_filter.split('/').peekable();
+    while let Some(level) = levels.next() {
+        match level {
+            "#" => return levels.peek().is_none(),
+            "+" => {}
+            level if level.contains(['+', '#']) => return false,
+            _ => {}
+        }
+    }
+
+    true
+}
+
+impl<const N: usize> MqttEncode for Subscribe<'_, N> {
+    type Error = MqttError;
+
+    /// Encodes the complete SUBSCRIBE packet, fixed header included
+    /// Returns the number of bytes written
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        let header = FixedHeader::new(ControlPacketType::SUBSCRIBE)?;
+        encode_head(
+            &header,
+            self.body_len(),
+            0,
+            buffer,
+            Utf8Validation::Strict,
+            |writer| self.encode_body(writer),
+        )
+    }
+
+    fn encoded_len(&self) -> usize {
+        packet_len(self.body_len())
+    }
+}
+
+impl<'a, const N: usize> MqttDecode<'a> for Subscribe<'a, N> {
+    type Error = MqttError;
+
+    /// Decodes a complete SUBSCRIBE packet, fixed header included
+    /// Returns the packet and the number of bytes it occupied
+    fn decode(buffer: &'a [u8]) -> Result<(Self, usize), Self::Error> {
+        decode_packet(buffer, ControlPacketType::SUBSCRIBE, Subscribe::decode_body)
+    }
+}
+
+/// Formatted as the packet identifier and each topic filter with its maximum QoS,
+/// followed by any properties
+impl<const N: usize> fmt::Display for Subscribe<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "packet_id={} subscriptions=[", self.packet_identifier)?;
+        for (index, subscription) in self.subscriptions().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(
+                f,
+                "{:?} qos={}",
+                subscription.topic_filter, subscription.options.maximum_qos as u8
+            )?;
+        }
+        f.write_str("]")?;
+
+        if !self.properties.is_empty() {
+            write!(f, " properties={}", self.properties)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_subscription_options {
+    use super::*;
+
+    #[test]
+    fn test_to_byte() {
+        let options = SubscriptionOptions {
+            maximum_qos: QOS::EXACTLYONCE,
+            no_local: true,
+            retain_as_published: true,
+            retain_handling: RetainHandling::DoNotSend,
+        };
+
+        assert_eq!(options.to_byte(), 0x2E);
+        assert_eq!(SubscriptionOptions::default().to_byte(), 0x00);
+    }
+
+    #[test]
+    fn test_reversibility() {
+        for options in (0..=u8::MAX).filter_map(|byte| SubscriptionOptions::from_byte(byte).ok()) {
+            assert_eq!(
+                SubscriptionOptions::try_from(u8::from(options)),
+                Ok(options)
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_byte_rejects_invalid_options() {
+        for options in [
+            0x40, // reserved bit 6 set
+            0x80, // reserved bit 7 set
+            0x03, // QoS 3
+            0x30, // retain handling 3
+        ] {
+            assert_eq!(
+                SubscriptionOptions::from_byte(options),
+                Err(MqttError::MalformedPacket)
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_subscribe {
+    use super::*;
+    use crate::packet::Packet;
+
+    #[test]
+    fn test_encode() {
+        let mut subscribe = Subscribe::<2>::new(10).unwrap();
+        subscribe
+            .properties
+            .push(Property::SubscriptionIdentifier(5))
+            .unwrap();
+        subscribe
+            .push(Subscription::new("a/+", QOS::ATLEASTONCE))
+            .unwrap();
+
+        let mut buffer = [0; 32];
+        let len = subscribe.encode(&mut buffer).unwrap();
+
+        assert_eq!(len, subscribe.encoded_len());
+        assert_eq!(
+            &buffer[..len],
+            &[
+                0x82, 0x0B, // SUBSCRIBE, remaining length 11
+                0x00, 0x0A, // packet identifier
+                0x02, 0x0B, 0x05, // subscription identifier of 5
+                0x00, 0x03, b'a', b'/', b'+', // topic filter
+                0x01, // maximum QoS 1
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_reversibility() {
+        let mut subscribe = Subscribe::<2>::new(0xFFFF).unwrap();
+        subscribe
+            .push(Subscription::new("#", QOS::EXACTLYONCE))
+            .unwrap();
+        subscribe
+            .push(Subscription {
+                topic_filter: "sensors/+/temperature",
+                options: SubscriptionOptions {
+                    maximum_qos: QOS::ATMOSTONCE,
+                    no_local: true,
+                    retain_as_published: false,
+                    retain_handling: RetainHandling::SendOnNewSubscription,
+                },
+            })
+            .unwrap();
+
+        let packet = Packet::Subscribe(subscribe);
+        let mut buffer = [0; 64];
+        let len = packet.encode(&mut buffer).unwrap();
+
+        assert_eq!(Packet::decode(&buffer[..len]), Ok((packet, len)));
+    }
+
+    #[test]
+    fn test_push_rejects_over_capacity() {
+        let mut subscribe = Subscribe::<1>::new(1).unwrap();
+        subscribe
+            .push(Subscription::new("a", QOS::ATMOSTONCE))
+            .unwrap();
+
+        assert_eq!(
+            subscribe.push(Subscription::new("b", QOS::ATMOSTONCE)),
+            Err(MqttError::SubscriptionsCapacityExceeded)
+        );
+    }
+
+    #[test]
+    fn test_encode_rejects_empty_subscriptions() {
+        let subscribe = Subscribe::<1>::new(1).unwrap();
+
+        let mut buffer = [0; 16];
+        assert_eq!(
+            subscribe.encode(&mut buffer),
+            Err(MqttError::EmptySubscriptions)
+        );
+    }
+
+    #[test]
+    fn test_encode_rejects_invalid_topic_filters() {
+        let mut buffer = [0; 32];
+
+        for topic_filter in ["", "a/#/b", "a/b#", "a+/b"] {
+            let mut subscribe = Subscribe::<1>::new(1).unwrap();
+            subscribe
+                .push(Subscription::new(topic_filter, QOS::ATMOSTONCE))
+                .unwrap();
+
+            assert_eq!(
+                subscribe.encode(&mut buffer),
+                Err(MqttError::InvalidTopicFilter)
+            );
+        }
+    }
+
+    #[test]
+    fn test_encode_rejects_other_properties() {
+        let mut subscribe = Subscribe::<1>::new(1).unwrap();
+        subscribe
+            .push(Subscription::new("a", QOS::ATMOSTONCE))
+            .unwrap();
+        subscribe
+            .properties
+            .push(Property::SubscriptionIdentifier(0))
+            .unwrap();
+
+        let mut buffer = [0; 16];
+        assert_eq!(
+            subscribe.encode(&mut buffer),
+            Err(MqttError::InvalidPropertyIdentifier)
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_empty_subscriptions() {
+        assert_eq!(
+            Subscribe::<1>::decode(&[0x82, 0x03, 0x00, 0x01, 0x00]),
+            Err(MqttError::MalformedPacket)
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_reserved_option_bits() {
+        let buffer = [0x82, 0x07, 0x00, 0x01, 0x00, 0x00, 0x01, b'a', 0x40];
+
+        assert_eq!(
+            Subscribe::<1>::decode(&buffer),
+            Err(MqttError::MalformedPacket)
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_header_flags() {
+        // SUBSCRIBE must have flags 0b0010 [MQTT-3.8.1-1]
+        let buffer = [0x80, 0x07, 0x00, 0x01, 0x00, 0x00, 0x01, b'a', 0x00];
+
+        assert_eq!(
+            Subscribe::<1>::decode(&buffer),
+            Err(MqttError::MalformedPacket)
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let mut subscribe = Subscribe::<2>::new(3).unwrap();
+        subscribe
+            .push(Subscription::new("a/#", QOS::ATLEASTONCE))
+            .unwrap();
+        subscribe
+            .push(Subscription::new("b", QOS::ATMOSTONCE))
+            .unwrap();
+
+        assert_eq!(
+            format!("{}", Packet::Subscribe(subscribe)),
+            "SUBSCRIBE packet_id=3 subscriptions=[\"a/#\" qos=1, \"b\" qos=0]"
+        );
+    }
+}
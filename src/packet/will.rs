@@ -0,0 +1,317 @@
+use super::publish::is_valid_topic_name;
+use super::{ConnectFlags, Missing, body_error, read_properties, write_properties};
+use crate::data_representation::{BinaryDataRef, ByteReader, ByteWriter, MqttEncode};
+use crate::error::MqttError;
+use crate::fixed_header::QOS;
+use crate::properties::{Properties, Property};
+
+/// The Will message the server publishes on the client's behalf if the connection
+/// ends without a DISCONNECT.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Will<'a, const N: usize> {
+    pub properties: Properties<'a, N>,
+    pub topic: &'a str,
+    pub payload: &'a [u8],
+    pub qos: QOS,
+    pub retain: bool,
+}
+
+impl<'a, const N: usize> Will<'a, N> {
+    /// Creates a QoS 0, non-retained Will with no properties
+    pub const fn new(topic: &'a str, payload: &'a [u8]) -> Self {
+        Self {
+            properties: Properties::new(),
+            topic,
+            payload,
+            qos: QOS::ATMOSTONCE,
+            retain: false,
+        }
+    }
+
+    /// Starts building a Will, with the topic still to be set
+    pub const fn builder() -> WillBuilder<'a, N, Missing> {
+        WillBuilder {
+            topic: Missing,
+            payload: &[],
+            qos: QOS::ATMOSTONCE,
+            retain: false,
+            properties: Properties::new(),
+        }
+    }
+
+    // returns the encoded size of the Will fields in the CONNECT payload
+    pub(crate) fn body_len(&self) -> usize {
+        self.properties.encoded_len() + 2 + self.topic.len() + 2 + self.payload.len()
+    }
+
+    // writes the Will Properties, Will Topic and Will Payload; the QoS and retain
+    // settings are carried in the connect flags instead
+    pub(crate) fn encode_body(&self, writer: &mut ByteWriter) -> Result<(), MqttError> {
+        if !self.properties.iter().all(is_will_property) {
+            return Err(MqttError::InvalidPropertyIdentifier);
+        }
+        // the Will Topic must be a valid topic name, and a Will has no Topic Alias [MQTT-3.1.3-11]
+        if !is_valid_topic_name(self.topic, false) {
+            return Err(MqttError::InvalidTopicName);
+        }
+
+        write_properties(writer, &self.properties)?;
+        writer.put_utf8_string(self.topic)?;
+        writer.put_encoded(&BinaryDataRef::new(self.payload)?)?;
+
+        Ok(())
+    }
+
+    // reads the Will fields, taking the QoS and retain settings from the connect flags
+    pub(crate) fn decode_body(
+        reader: &mut ByteReader<'a>,
+        flags: &ConnectFlags,
+    ) -> Result<Self, MqttError> {
+        let properties: Properties<'a, N> = read_properties(reader)?;
+        if !properties.iter().all(is_will_property) {
+            return Err(MqttError::MalformedProperties);
+        }
+
+        let topic = reader.read_utf8_str().map_err(body_error)?;
+        if !is_valid_topic_name(topic, false) {
+            return Err(MqttError::MalformedPacket);
+        }
+        let payload: BinaryDataRef = reader.read_decoded().map_err(body_error)?;
+
+        Ok(Self {
+            properties,
+            topic,
+            payload: payload.as_bytes(),
+            qos: flags.will_qos,
+            retain: flags.will_retain,
+        })
+    }
+}
+
+// whether the property may appear among the Will Properties
+fn is_will_property(property: &Property) -> bool {
+    matches!(
+        property,
+        Property::WillDelayInterval(_)
+            | Property::PayloadFormatIndicator(_)
+            | Property::MessageExpiryInterval(_)
+            | Property::ContentType(_)
+            | Property::ResponseTopic(_)
+            | Property::CorrelationData(_)
+            | Property::UserProperty(_, _)
+    )
+}
+
+/// Builds a `Will`. `build` is only available once the topic is set:
+///
+/// ```compile_fail
+/// let will = midge::packet::Will::<0>::builder().payload(b"offline").build();
+/// ```
+///
+/// The payload defaults to empty, the QoS to 0, retain to false, and the properties to none.
+#[derive(Debug, Clone)]
+pub struct WillBuilder<'a, const N: usize, T> {
+    topic: T,
+    payload: &'a [u8],
+    qos: QOS,
+    retain: bool,
+    properties: Properties<'a, N>,
+}
+
+impl<'a, const N: usize, T> WillBuilder<'a, N, T> {
+    /// Sets the topic the Will is published to
+    pub fn topic(self, topic: &'a str) -> WillBuilder<'a, N, &'a str> {
+        WillBuilder {
+            topic,
+            payload: self.payload,
+            qos: self.qos,
+            retain: self.retain,
+            properties: self.properties,
+        }
+    }
+
+    /// Sets the payload
+    pub fn payload(mut self, payload: &'a [u8]) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    /// Sets the QoS the Will is published at
+    pub fn qos(mut self, qos: QOS) -> Self {
+        self.qos = qos;
+        self
+    }
+
+    /// Sets whether the Will is published as a retained message
+    pub fn retain(mut self, retain: bool) -> Self {
+        self.retain = retain;
+        self
+    }
+
+    /// Sets the properties, replacing any added so far
+    pub fn properties(mut self, properties: Properties<'a, N>) -> Self {
+        self.properties = properties;
+        self
+    }
+
+    /// Adds a property, rejecting those not allowed in a Will
+    pub fn property(mut self, property: Property<'a>) -> Result<Self, MqttError> {
+        if !is_will_property(&property) {
+            return Err(MqttError::InvalidPropertyIdentifier);
+        }
+
+        self.properties.push(property)?;
+        Ok(self)
+    }
+
+    /// Adds a Will Delay Interval: the seconds the server waits after the connection ends
+    /// before publishing the Will
+    pub fn will_delay_interval(self, secs: u32) -> Result<Self, MqttError> {
+        self.property(Property::WillDelayInterval(secs))
+    }
+
+    /// Adds a Message Expiry Interval: the lifetime, in seconds, of the published Will
+    pub fn message_expiry_interval(self, secs: u32) -> Result<Self, MqttError> {
+        self.property(Property::MessageExpiryInterval(secs))
+    }
+
+    /// Adds a Content Type describing the payload, e.g. a MIME type
+    pub fn content_type(self, content_type: &'a str) -> Result<Self, MqttError> {
+        self.property(Property::ContentType(content_type))
+    }
+}
+
+impl<'a, const N: usize> WillBuilder<'a, N, &'a str> {
+    /// Builds the Will
+    pub fn build(self) -> Will<'a, N> {
+        Will {
+            properties: self.properties,
+            topic: self.topic,
+            payload: self.payload,
+            qos: self.qos,
+            retain: self.retain,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_will {
+    use super::*;
+
+    #[test]
+    fn test_builder_defaults() {
+        let will = Will::<0>::builder().topic("status").build();
+
+        assert_eq!(will, Will::new("status", &[]));
+    }
+
+    #[test]
+    fn test_builder_sets_every_field() {
+        let will = Will::<3>::builder()
+            .payload(b"offline")
+            .qos(QOS::ATLEASTONCE)
+            .retain(true)
+            .will_delay_interval(30)
+            .and_then(|builder| builder.message_expiry_interval(3600))
+            .and_then(|builder| builder.content_type("text/plain"))
+            .unwrap()
+            .topic("status")
+            .build();
+
+        let mut properties = Properties::<3>::new();
+        properties.push(Property::WillDelayInterval(30)).unwrap();
+        properties
+            .push(Property::MessageExpiryInterval(3600))
+            .unwrap();
+        properties
+            .push(Property::ContentType("text/plain"))
+            .unwrap();
+
+        assert_eq!(
+            will,
+            Will {
+                properties,
+                topic: "status",
+                payload: b"offline",
+                qos: QOS::ATLEASTONCE,
+                retain: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_builder_rejects_other_properties() {
+        let result = Will::<1>::builder().property(Property::SessionExpiryInterval(10));
+
+        assert!(matches!(result, Err(MqttError::InvalidPropertyIdentifier)));
+    }
+
+    #[test]
+    fn test_builder_rejects_too_many_properties() {
+        let result = Will::<1>::builder()
+            .will_delay_interval(30)
+            .and_then(|builder| builder.content_type("text/plain"));
+
+        assert!(matches!(result, Err(MqttError::PropertiesCapacityExceeded)));
+    }
+
+    #[test]
+    fn test_encode_rejects_other_properties() {
+        let mut will = Will::<1>::new("status", b"offline");
+        will.properties
+            .push(Property::SessionExpiryInterval(10))
+            .unwrap();
+
+        let mut buffer = [0; 32];
+        assert_eq!(
+            will.encode_body(&mut ByteWriter::new(&mut buffer)),
+            Err(MqttError::InvalidPropertyIdentifier)
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_other_properties() {
+        let flags = ConnectFlags::from_byte(0x04).unwrap();
+        let body = [
+            0x05, 0x11, 0x00, 0x00, 0x00, 0x0A, // session expiry interval of 10 seconds
+            0x00, 0x01, b't', // will topic
+            0x00, 0x00, // empty will payload
+        ];
+
+        assert_eq!(
+            Will::<1>::decode_body(&mut ByteReader::new(&body), &flags),
+            Err(MqttError::MalformedProperties)
+        );
+    }
+
+    #[test]
+    fn test_encode_rejects_invalid_topic_names() {
+        let mut buffer = [0; 32];
+
+        for topic in ["", "status/+", "status/#"] {
+            assert_eq!(
+                Will::<0>::new(topic, b"offline").encode_body(&mut ByteWriter::new(&mut buffer)),
+                Err(MqttError::InvalidTopicName)
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_topic_names() {
+        let flags = ConnectFlags::from_byte(0x04).unwrap();
+
+        for topic in [&b""[..], b"+", b"a/#"] {
+            let mut body = [0; 8];
+            body[2] = topic.len() as u8; // no properties, then the will topic length
+            body[3..3 + topic.len()].copy_from_slice(topic);
+            let len = 3 + topic.len() + 2; // and an empty will payload
+
+            assert_eq!(
+                Will::<0>::decode_body(&mut ByteReader::new(&body[..len]), &flags),
+                Err(MqttError::MalformedPacket)
+            );
+        }
+    }
+}
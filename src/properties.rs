@@ -0,0 +1,969 @@
+//! MQTT 5 properties: the length-prefixed block of identifier/value pairs
+//! carried in the variable header of most packets (and in the will properties of CONNECT).
+//! The property length is encoded as a Variable Byte Integer, followed by the properties themselves.
+
+use crate::data_representation::{
+    BinaryDataRef, DataRepresentationError, FourByteInt, MqttDecode, MqttEncode, TwoByteInt,
+    Utf8StrRef, Utf8Validation, VariableByteInt,
+};
+use crate::error::MqttError;
+use core::fmt;
+
+/// Identifiers of the MQTT 5 properties. Although encoded as a Variable Byte Integer,
+/// every defined identifier fits in a single byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum PropertyIdentifier {
+    PayloadFormatIndicator = 0x01,
+    MessageExpiryInterval = 0x02,
+    ContentType = 0x03,
+    ResponseTopic = 0x08,
+    CorrelationData = 0x09,
+    SubscriptionIdentifier = 0x0B,
+    SessionExpiryInterval = 0x11,
+    AssignedClientIdentifier = 0x12,
+    ServerKeepAlive = 0x13,
+    AuthenticationMethod = 0x15,
+    AuthenticationData = 0x16,
+    RequestProblemInformation = 0x17,
+    WillDelayInterval = 0x18,
+    RequestResponseInformation = 0x19,
+    ResponseInformation = 0x1A,
+    ServerReference = 0x1C,
+    ReasonString = 0x1F,
+    ReceiveMaximum = 0x21,
+    TopicAliasMaximum = 0x22,
+    TopicAlias = 0x23,
+    MaximumQoS = 0x24,
+    RetainAvailable = 0x25,
+    UserProperty = 0x26,
+    MaximumPacketSize = 0x27,
+    WildcardSubscriptionAvailable = 0x28,
+    SubscriptionIdentifierAvailable = 0x29,
+    SharedSubscriptionAvailable = 0x2A,
+}
+
+impl TryFrom<u8> for PropertyIdentifier {
+    type Error = MqttError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x01 => Ok(PropertyIdentifier::PayloadFormatIndicator),
+            0x02 => Ok(PropertyIdentifier::MessageExpiryInterval),
+            0x03 => Ok(PropertyIdentifier::ContentType),
+            0x08 => Ok(PropertyIdentifier::ResponseTopic),
+            0x09 => Ok(PropertyIdentifier::CorrelationData),
+            0x0B => Ok(PropertyIdentifier::SubscriptionIdentifier),
+            0x11 => Ok(PropertyIdentifier::SessionExpiryInterval),
+            0x12 => Ok(PropertyIdentifier::AssignedClientIdentifier),
+            0x13 => Ok(PropertyIdentifier::ServerKeepAlive),
+            0x15 => Ok(PropertyIdentifier::AuthenticationMethod),
+            0x16 => Ok(PropertyIdentifier::AuthenticationData),
+            0x17 => Ok(PropertyIdentifier::RequestProblemInformation),
+            0x18 => Ok(PropertyIdentifier::WillDelayInterval),
+            0x19 => Ok(PropertyIdentifier::RequestResponseInformation),
+            0x1A => Ok(PropertyIdentifier::ResponseInformation),
+            0x1C => Ok(PropertyIdentifier::ServerReference),
+            0x1F => Ok(PropertyIdentifier::ReasonString),
+            0x21 => Ok(PropertyIdentifier::ReceiveMaximum),
+            0x22 => Ok(PropertyIdentifier::TopicAliasMaximum),
+            0x23 => Ok(PropertyIdentifier::TopicAlias),
+            0x24 => Ok(PropertyIdentifier::MaximumQoS),
+            0x25 => Ok(PropertyIdentifier::RetainAvailable),
+            0x26 => Ok(PropertyIdentifier::UserProperty),
+            0x27 => Ok(PropertyIdentifier::MaximumPacketSize),
+            0x28 => Ok(PropertyIdentifier::WildcardSubscriptionAvailable),
+            0x29 => Ok(PropertyIdentifier::SubscriptionIdentifierAvailable),
+            0x2A => Ok(PropertyIdentifier::SharedSubscriptionAvailable),
+            _ => Err(MqttError::InvalidPropertyIdentifier),
+        }
+    }
+}
+
+/// A single MQTT 5 property. String and binary values borrow from the
+/// buffer they were decoded from (or from the caller, when encoding).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Property<'a> {
+    PayloadFormatIndicator(u8),
+    MessageExpiryInterval(u32),
+    ContentType(&'a str),
+    ResponseTopic(&'a str),
+    CorrelationData(&'a [u8]),
+    SubscriptionIdentifier(u32), // encoded as a Variable Byte Integer
+    SessionExpiryInterval(u32),
+    AssignedClientIdentifier(&'a str),
+    ServerKeepAlive(u16),
+    AuthenticationMethod(&'a str),
+    AuthenticationData(&'a [u8]),
+    RequestProblemInformation(u8),
+    WillDelayInterval(u32),
+    RequestResponseInformation(u8),
+    ResponseInformation(&'a str),
+    ServerReference(&'a str),
+    ReasonString(&'a str),
+    ReceiveMaximum(u16),
+    TopicAliasMaximum(u16),
+    TopicAlias(u16),
+    MaximumQoS(u8),
+    RetainAvailable(u8),
+    UserProperty(&'a str, &'a str), // name, value
+    MaximumPacketSize(u32),
+    WildcardSubscriptionAvailable(u8),
+    SubscriptionIdentifierAvailable(u8),
+    SharedSubscriptionAvailable(u8),
+}
+
+impl<'a> Property<'a> {
+    /// Returns the identifier of this property
+    pub fn identifier(&self) -> PropertyIdentifier {
+        match self {
+            Property::PayloadFormatIndicator(_) => PropertyIdentifier::PayloadFormatIndicator,
+            Property::MessageExpiryInterval(_) => PropertyIdentifier::MessageExpiryInterval,
+            Property::ContentType(_) => PropertyIdentifier::ContentType,
+            Property::ResponseTopic(_) => PropertyIdentifier::ResponseTopic,
+            Property::CorrelationData(_) => PropertyIdentifier::CorrelationData,
+            Property::SubscriptionIdentifier(_) => PropertyIdentifier::SubscriptionIdentifier,
+            Property::SessionExpiryInterval(_) => PropertyIdentifier::SessionExpiryInterval,
+            Property::AssignedClientIdentifier(_) => PropertyIdentifier::AssignedClientIdentifier,
+            Property::ServerKeepAlive(_) => PropertyIdentifier::ServerKeepAlive,
+            Property::AuthenticationMethod(_) => PropertyIdentifier::AuthenticationMethod,
+            Property::AuthenticationData(_) => PropertyIdentifier::AuthenticationData,
+            Property::RequestProblemInformation(_) => PropertyIdentifier::RequestProblemInformation,
+            Property::WillDelayInterval(_) => PropertyIdentifier::WillDelayInterval,
+            Property::RequestResponseInformation(_) => {
+                PropertyIdentifier::RequestResponseInformation
+            }
+            Property::ResponseInformation(_) => PropertyIdentifier::ResponseInformation,
+            Property::ServerReference(_) => PropertyIdentifier::ServerReference,
+            Property::ReasonString(_) => PropertyIdentifier::ReasonString,
+            Property::ReceiveMaximum(_) => PropertyIdentifier::ReceiveMaximum,
+            Property::TopicAliasMaximum(_) => PropertyIdentifier::TopicAliasMaximum,
+            Property::TopicAlias(_) => PropertyIdentifier::TopicAlias,
+            Property::MaximumQoS(_) => PropertyIdentifier::MaximumQoS,
+            Property::RetainAvailable(_) => PropertyIdentifier::RetainAvailable,
+            Property::UserProperty(_, _) => PropertyIdentifier::UserProperty,
+            Property::MaximumPacketSize(_) => PropertyIdentifier::MaximumPacketSize,
+            Property::WildcardSubscriptionAvailable(_) => {
+                PropertyIdentifier::WildcardSubscriptionAvailable
+            }
+            Property::SubscriptionIdentifierAvailable(_) => {
+                PropertyIdentifier::SubscriptionIdentifierAvailable
+            }
+            Property::SharedSubscriptionAvailable(_) => {
+                PropertyIdentifier::SharedSubscriptionAvailable
+            }
+        }
+    }
+}
+
+impl MqttEncode for Property<'_> {
+    type Error = MqttError;
+
+    /// Encodes the property (identifier and value) into the buffer
+    /// Returns the number of bytes written
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        self.encode_with_validation(buffer, Utf8Validation::Strict)
+    }
+
+    /// Returns the number of bytes this property occupies when encoded, including its identifier
+    fn encoded_len(&self) -> usize {
+        let value_len = match self {
+            Property::PayloadFormatIndicator(_)
+            | Property::RequestProblemInformation(_)
+            | Property::RequestResponseInformation(_)
+            | Property::MaximumQoS(_)
+            | Property::RetainAvailable(_)
+            | Property::WildcardSubscriptionAvailable(_)
+            | Property::SubscriptionIdentifierAvailable(_)
+            | Property::SharedSubscriptionAvailable(_) => 1,
+            Property::ServerKeepAlive(_)
+            | Property::ReceiveMaximum(_)
+            | Property::TopicAliasMaximum(_)
+            | Property::TopicAlias(_) => 2,
+            Property::MessageExpiryInterval(_)
+            | Property::SessionExpiryInterval(_)
+            | Property::WillDelayInterval(_)
+            | Property::MaximumPacketSize(_) => 4,
+            Property::SubscriptionIdentifier(value) => VariableByteInt::new(*value)
+                .map(VariableByteInt::length)
+                .unwrap_or(4),
+            Property::ContentType(value)
+            | Property::ResponseTopic(value)
+            | Property::AssignedClientIdentifier(value)
+            | Property::AuthenticationMethod(value)
+            | Property::ResponseInformation(value)
+            | Property::ServerReference(value)
+            | Property::ReasonString(value) => 2 + value.len(),
+            Property::CorrelationData(value) | Property::AuthenticationData(value) => {
+                2 + value.len()
+            }
+            Property::UserProperty(name, value) => 4 + name.len() + value.len(),
+        };
+
+        1 + value_len // identifier is a single byte for every defined property
+    }
+}
+
+impl<'a> Property<'a> {
+    /// Encodes the property (identifier and value) into the buffer, validating string values
+    /// as given. Returns the number of bytes written
+    pub fn encode_with_validation(
+        &self,
+        buffer: &mut [u8],
+        validation: Utf8Validation,
+    ) -> Result<usize, MqttError> {
+        let (identifier, value_buffer) =
+            buffer.split_first_mut().ok_or(MqttError::BufferOverflow)?;
+        *identifier = self.identifier() as u8;
+
+        let value_len = match *self {
+            Property::PayloadFormatIndicator(value)
+            | Property::RequestProblemInformation(value)
+            | Property::RequestResponseInformation(value)
+            | Property::MaximumQoS(value)
+            | Property::RetainAvailable(value)
+            | Property::WildcardSubscriptionAvailable(value)
+            | Property::SubscriptionIdentifierAvailable(value)
+            | Property::SharedSubscriptionAvailable(value) => encode_bytes(&[value], value_buffer)?,
+            Property::ServerKeepAlive(value)
+            | Property::ReceiveMaximum(value)
+            | Property::TopicAliasMaximum(value)
+            | Property::TopicAlias(value) => {
+                encode_bytes(&TwoByteInt::from(value).to_bytes(), value_buffer)?
+            }
+            Property::MessageExpiryInterval(value)
+            | Property::SessionExpiryInterval(value)
+            | Property::WillDelayInterval(value)
+            | Property::MaximumPacketSize(value) => {
+                encode_bytes(&FourByteInt::from(value).to_bytes(), value_buffer)?
+            }
+            Property::SubscriptionIdentifier(value) => {
+                encode_vbi(VariableByteInt::new(value)?, value_buffer)?
+            }
+            Property::ContentType(value)
+            | Property::ResponseTopic(value)
+            | Property::AssignedClientIdentifier(value)
+            | Property::AuthenticationMethod(value)
+            | Property::ResponseInformation(value)
+            | Property::ServerReference(value)
+            | Property::ReasonString(value) => encode_str(value, validation, value_buffer)?,
+            Property::CorrelationData(value) | Property::AuthenticationData(value) => {
+                encode_binary(value, value_buffer)?
+            }
+            Property::UserProperty(name, value) => {
+                let name_len = encode_str(name, validation, value_buffer)?;
+                let value_target = value_buffer
+                    .get_mut(name_len..)
+                    .ok_or(MqttError::BufferOverflow)?;
+                name_len + encode_str(value, validation, value_target)?
+            }
+        };
+
+        Ok(1 + value_len)
+    }
+
+    /// Decodes a single property from the start of the buffer, validating string values
+    /// as given. Returns the property and the number of bytes it occupied
+    pub fn decode_with_validation(
+        buffer: &'a [u8],
+        validation: Utf8Validation,
+    ) -> Result<(Self, usize), MqttError> {
+        // the identifier is a Variable Byte Integer, though every defined identifier fits in one byte
+        let (identifier_vbi, identifier_len) = VariableByteInt::decode(buffer)?;
+        let value_buffer = buffer
+            .get(identifier_len..)
+            .ok_or(MqttError::MalformedProperties)?;
+
+        let identifier = u8::try_from(identifier_vbi.value())
+            .map_err(|_| MqttError::InvalidPropertyIdentifier)
+            .and_then(PropertyIdentifier::try_from)?;
+
+        let (property, value_len) = match identifier {
+            PropertyIdentifier::PayloadFormatIndicator => {
+                decode_u8(value_buffer, Property::PayloadFormatIndicator)?
+            }
+            PropertyIdentifier::MessageExpiryInterval => {
+                decode_u32(value_buffer, Property::MessageExpiryInterval)?
+            }
+            PropertyIdentifier::ContentType => {
+                decode_str(value_buffer, validation, Property::ContentType)?
+            }
+            PropertyIdentifier::ResponseTopic => {
+                decode_str(value_buffer, validation, Property::ResponseTopic)?
+            }
+            PropertyIdentifier::CorrelationData => {
+                decode_binary(value_buffer, Property::CorrelationData)?
+            }
+            PropertyIdentifier::SubscriptionIdentifier => {
+                let (vbi, vbi_len) = VariableByteInt::decode(value_buffer)?;
+                (Property::SubscriptionIdentifier(vbi.value()), vbi_len)
+            }
+            PropertyIdentifier::SessionExpiryInterval => {
+                decode_u32(value_buffer, Property::SessionExpiryInterval)?
+            }
+            PropertyIdentifier::AssignedClientIdentifier => {
+                decode_str(value_buffer, validation, Property::AssignedClientIdentifier)?
+            }
+            PropertyIdentifier::ServerKeepAlive => {
+                decode_u16(value_buffer, Property::ServerKeepAlive)?
+            }
+            PropertyIdentifier::AuthenticationMethod => {
+                decode_str(value_buffer, validation, Property::AuthenticationMethod)?
+            }
+            PropertyIdentifier::AuthenticationData => {
+                decode_binary(value_buffer, Property::AuthenticationData)?
+            }
+            PropertyIdentifier::RequestProblemInformation => {
+                decode_u8(value_buffer, Property::RequestProblemInformation)?
+            }
+            PropertyIdentifier::WillDelayInterval => {
+                decode_u32(value_buffer, Property::WillDelayInterval)?
+            }
+            PropertyIdentifier::RequestResponseInformation => {
+                decode_u8(value_buffer, Property::RequestResponseInformation)?
+            }
+            PropertyIdentifier::ResponseInformation => {
+                decode_str(value_buffer, validation, Property::ResponseInformation)?
+            }
+            PropertyIdentifier::ServerReference => {
+                decode_str(value_buffer, validation, Property::ServerReference)?
+            }
+            PropertyIdentifier::ReasonString => {
+                decode_str(value_buffer, validation, Property::ReasonString)?
+            }
+            PropertyIdentifier::ReceiveMaximum => {
+                decode_u16(value_buffer, Property::ReceiveMaximum)?
+            }
+            PropertyIdentifier::TopicAliasMaximum => {
+                decode_u16(value_buffer, Property::TopicAliasMaximum)?
+            }
+            PropertyIdentifier::TopicAlias => decode_u16(value_buffer, Property::TopicAlias)?,
+            PropertyIdentifier::MaximumQoS => decode_u8(value_buffer, Property::MaximumQoS)?,
+            PropertyIdentifier::RetainAvailable => {
+                decode_u8(value_buffer, Property::RetainAvailable)?
+            }
+            PropertyIdentifier::UserProperty => {
+                let (name, name_len) = decode_str(value_buffer, validation, |name| name)?;
+                let value_buffer = value_buffer
+                    .get(name_len..)
+                    .ok_or(MqttError::MalformedProperties)?;
+                let (value, value_len) = decode_str(value_buffer, validation, |value| value)?;
+                (Property::UserProperty(name, value), name_len + value_len)
+            }
+            PropertyIdentifier::MaximumPacketSize => {
+                decode_u32(value_buffer, Property::MaximumPacketSize)?
+            }
+            PropertyIdentifier::WildcardSubscriptionAvailable => {
+                decode_u8(value_buffer, Property::WildcardSubscriptionAvailable)?
+            }
+            PropertyIdentifier::SubscriptionIdentifierAvailable => {
+                decode_u8(value_buffer, Property::SubscriptionIdentifierAvailable)?
+            }
+            PropertyIdentifier::SharedSubscriptionAvailable => {
+                decode_u8(value_buffer, Property::SharedSubscriptionAvailable)?
+            }
+        };
+
+        Ok((property, identifier_len + value_len))
+    }
+}
+
+impl<'a> MqttDecode<'a> for Property<'a> {
+    type Error = MqttError;
+
+    /// Decodes a single property from the start of the buffer
+    /// Returns the property and the number of bytes it occupied
+    fn decode(buffer: &'a [u8]) -> Result<(Self, usize), Self::Error> {
+        Property::decode_with_validation(buffer, Utf8Validation::Strict)
+    }
+}
+
+/// A length-prefixed block of up to N properties
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Properties<'a, const N: usize> {
+    properties: [Option<Property<'a>>; N],
+    len: usize,
+}
+
+impl<'a, const N: usize> Properties<'a, N> {
+    /// Creates an empty property block
+    pub const fn new() -> Self {
+        Self {
+            properties: [None; N],
+            len: 0,
+        }
+    }
+
+    /// Appends a property, if space allows
+    pub fn push(&mut self, property: Property<'a>) -> Result<(), MqttError> {
+        let slot = self
+            .properties
+            .get_mut(self.len)
+            .ok_or(MqttError::PropertiesCapacityExceeded)?;
+        *slot = Some(property);
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Returns the number of properties held
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the block holds no properties
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over the held properties, in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = &Property<'a>> {
+        self.properties.iter().flatten()
+    }
+
+    /// Encodes the Property Length followed by each property, validating string values
+    /// as given. Returns the number of bytes written
+    pub fn encode_with_validation(
+        &self,
+        buffer: &mut [u8],
+        validation: Utf8Validation,
+    ) -> Result<usize, MqttError> {
+        let properties_len =
+            u32::try_from(self.properties_len()).map_err(|_| MqttError::MalformedProperties)?;
+        let length = VariableByteInt::new(properties_len)?;
+
+        let mut position = encode_vbi(length, buffer)?;
+
+        for property in self.iter() {
+            let target = buffer
+                .get_mut(position..)
+                .ok_or(MqttError::BufferOverflow)?;
+            position += property.encode_with_validation(target, validation)?;
+        }
+
+        Ok(position)
+    }
+
+    /// Returns the value of the Property Length field: the encoded size of the properties,
+    /// excluding the length field itself
+    pub fn properties_len(&self) -> usize {
+        self.iter().map(Property::encoded_len).sum()
+    }
+}
+
+impl<const N: usize> MqttEncode for Properties<'_, N> {
+    type Error = MqttError;
+
+    /// Encodes the Property Length followed by each property
+    /// Returns the number of bytes written
+    fn encode(&self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        self.encode_with_validation(buffer, Utf8Validation::Strict)
+    }
+
+    /// Returns the number of bytes the block occupies when encoded, including the Property Length
+    fn encoded_len(&self) -> usize {
+        let properties_len = self.properties_len();
+        let length_len = u32::try_from(properties_len)
+            .ok()
+            .and_then(|len| VariableByteInt::new(len).ok())
+            .map(VariableByteInt::length)
+            .unwrap_or(4);
+
+        length_len + properties_len
+    }
+}
+
+impl<'a, const N: usize> MqttDecode<'a> for Properties<'a, N> {
+    type Error = MqttError;
+
+    /// Decodes a property block from the start of the buffer
+    /// Returns the properties and the number of bytes the block occupied
+    fn decode(buffer: &'a [u8]) -> Result<(Self, usize), Self::Error> {
+        Properties::decode_with_validation(buffer, Utf8Validation::Strict)
+    }
+}
+
+impl<'a, const N: usize> Properties<'a, N> {
+    /// Decodes a property block from the start of the buffer, validating string values
+    /// as given. Returns the properties and the number of bytes the block occupied
+    pub fn decode_with_validation(
+        buffer: &'a [u8],
+        validation: Utf8Validation,
+    ) -> Result<(Self, usize), MqttError> {
+        let (length, length_len) = VariableByteInt::decode(buffer)?;
+        let end = length_len + length.value() as usize;
+
+        // the properties must fit exactly within the declared Property Length
+        let mut remaining = buffer
+            .get(length_len..end)
+            .ok_or(MqttError::MalformedProperties)?;
+
+        let mut properties = Self::new();
+        while !remaining.is_empty() {
+            let (property, consumed) = Property::decode_with_validation(remaining, validation)?;
+            properties.push(property)?;
+            remaining = remaining
+                .get(consumed..)
+                .ok_or(MqttError::MalformedProperties)?;
+        }
+
+        Ok((properties, end))
+    }
+}
+
+/// Formatted as `Identifier=value`, with strings quoted and binary data in hex
+impl fmt::Display for Property<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let identifier = self.identifier();
+
+        match self {
+            Property::PayloadFormatIndicator(value)
+            | Property::RequestProblemInformation(value)
+            | Property::RequestResponseInformation(value)
+            | Property::MaximumQoS(value)
+            | Property::RetainAvailable(value)
+            | Property::WildcardSubscriptionAvailable(value)
+            | Property::SubscriptionIdentifierAvailable(value)
+            | Property::SharedSubscriptionAvailable(value) => write!(f, "{identifier:?}={value}"),
+            Property::ServerKeepAlive(value)
+            | Property::ReceiveMaximum(value)
+            | Property::TopicAliasMaximum(value)
+            | Property::TopicAlias(value) => write!(f, "{identifier:?}={value}"),
+            Property::MessageExpiryInterval(value)
+            | Property::SubscriptionIdentifier(value)
+            | Property::SessionExpiryInterval(value)
+            | Property::WillDelayInterval(value)
+            | Property::MaximumPacketSize(value) => write!(f, "{identifier:?}={value}"),
+            Property::ContentType(value)
+            | Property::ResponseTopic(value)
+            | Property::AssignedClientIdentifier(value)
+            | Property::AuthenticationMethod(value)
+            | Property::ResponseInformation(value)
+            | Property::ServerReference(value)
+            | Property::ReasonString(value) => write!(f, "{identifier:?}={value:?}"),
+            Property::CorrelationData(value) | Property::AuthenticationData(value) => {
+                write!(f, "{identifier:?}={value:02x?}")
+            }
+            Property::UserProperty(name, value) => write!(f, "{identifier:?}={name:?}:{value:?}"),
+        }
+    }
+}
+
+/// Formatted as a braced, comma-separated list of properties
+impl<const N: usize> fmt::Display for Properties<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("{")?;
+        for (index, property) in self.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{property}")?;
+        }
+        f.write_str("}")
+    }
+}
+
+impl<const N: usize> Default for Properties<'_, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// fills up to N slots; the properties themselves are unconstrained, so not every block encodes
+#[cfg(feature = "arbitrary")]
+impl<'a, const N: usize> arbitrary::Arbitrary<'a> for Properties<'a, N> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut properties = Self::new();
+        for _ in 0..u.int_in_range(0..=N)? {
+            properties
+                .push(u.arbitrary()?)
+                .map_err(|_| arbitrary::Error::IncorrectFormat)?;
+        }
+
+        Ok(properties)
+    }
+}
+
+fn encode_bytes(bytes: &[u8], buffer: &mut [u8]) -> Result<usize, MqttError> {
+    buffer
+        .get_mut(..bytes.len())
+        .ok_or(MqttError::BufferOverflow)?
+        .copy_from_slice(bytes);
+
+    Ok(bytes.len())
+}
+
+fn encode_vbi(value: VariableByteInt, buffer: &mut [u8]) -> Result<usize, MqttError> {
+    value.encode(buffer).map_err(|error| match error {
+        DataRepresentationError::VariableByteIntegerBufferOverflow => MqttError::BufferOverflow,
+        error => error.into(),
+    })
+}
+
+fn encode_binary(value: &[u8], buffer: &mut [u8]) -> Result<usize, MqttError> {
+    BinaryDataRef::new(value)?
+        .encode(buffer)
+        .map_err(|error| match error {
+            DataRepresentationError::BinaryBufferOverflow => MqttError::BufferOverflow,
+            error => error.into(),
+        })
+}
+
+fn encode_str(
+    value: &str,
+    validation: Utf8Validation,
+    buffer: &mut [u8],
+) -> Result<usize, MqttError> {
+    Utf8StrRef::with_validation(value, validation)?
+        .encode(buffer)
+        .map_err(|error| match error {
+            DataRepresentationError::Utf8BufferOverflow => MqttError::BufferOverflow,
+            error => error.into(),
+        })
+}
+
+fn decode_u8<'a>(
+    buffer: &[u8],
+    property: fn(u8) -> Property<'a>,
+) -> Result<(Property<'a>, usize), MqttError> {
+    let value = buffer.first().ok_or(MqttError::MalformedProperties)?;
+
+    Ok((property(*value), 1))
+}
+
+fn decode_u16<'a>(
+    buffer: &[u8],
+    property: fn(u16) -> Property<'a>,
+) -> Result<(Property<'a>, usize), MqttError> {
+    let (bytes, _) = buffer
+        .split_first_chunk::<2>()
+        .ok_or(MqttError::MalformedProperties)?;
+
+    Ok((property(TwoByteInt::from_bytes(*bytes).value()), 2))
+}
+
+fn decode_u32<'a>(
+    buffer: &[u8],
+    property: fn(u32) -> Property<'a>,
+) -> Result<(Property<'a>, usize), MqttError> {
+    let (bytes, _) = buffer
+        .split_first_chunk::<4>()
+        .ok_or(MqttError::MalformedProperties)?;
+
+    Ok((property(FourByteInt::from_bytes(*bytes).value()), 4))
+}
+
+fn decode_binary<'a, T>(
+    buffer: &'a [u8],
+    property: impl FnOnce(&'a [u8]) -> T,
+) -> Result<(T, usize), MqttError> {
+    let (value, consumed) = BinaryDataRef::decode(buffer).map_err(|error| match error {
+        DataRepresentationError::BinaryMalformedBuffer => MqttError::MalformedProperties,
+        error => error.into(),
+    })?;
+
+    Ok((property(value.as_bytes()), consumed))
+}
+
+fn decode_str<'a, T>(
+    buffer: &'a [u8],
+    validation: Utf8Validation,
+    property: impl FnOnce(&'a str) -> T,
+) -> Result<(T, usize), MqttError> {
+    let (value, consumed) =
+        Utf8StrRef::decode_with_validation(buffer, validation).map_err(|error| match error {
+            DataRepresentationError::Utf8MalformedBuffer => MqttError::MalformedProperties,
+            error => error.into(),
+        })?;
+
+    Ok((property(value.as_str()), consumed))
+}
+
+#[cfg(test)]
+mod test_property_identifier {
+    use super::*;
+
+    #[test]
+    fn test_reversibility() {
+        for byte in 0..=u8::MAX {
+            if let Ok(identifier) = PropertyIdentifier::try_from(byte) {
+                assert_eq!(identifier as u8, byte);
+            }
+        }
+    }
+
+    #[test]
+    fn converts_known_identifiers() {
+        assert_eq!(
+            PropertyIdentifier::try_from(0x01),
+            Ok(PropertyIdentifier::PayloadFormatIndicator)
+        );
+        assert_eq!(
+            PropertyIdentifier::try_from(0x26),
+            Ok(PropertyIdentifier::UserProperty)
+        );
+        assert_eq!(
+            PropertyIdentifier::try_from(0x2A),
+            Ok(PropertyIdentifier::SharedSubscriptionAvailable)
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_identifiers() {
+        for byte in [0x00, 0x04, 0x0A, 0x14, 0x1B, 0x20, 0x2B, 0xFF] {
+            assert_eq!(
+                PropertyIdentifier::try_from(byte),
+                Err(MqttError::InvalidPropertyIdentifier)
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_property {
+    use super::*;
+
+    #[test]
+    fn encodes_four_byte_property() {
+        let property = Property::SessionExpiryInterval(0x0000_0E10);
+        let mut buffer = [0; 5];
+
+        let encoded_len = property.encode(&mut buffer).unwrap();
+
+        assert_eq!(encoded_len, 5);
+        assert_eq!(buffer, [0x11, 0x00, 0x00, 0x0E, 0x10]);
+    }
+
+    #[test]
+    fn encodes_user_property() {
+        let property = Property::UserProperty("a", "bc");
+        let mut buffer = [0; 8];
+
+        let encoded_len = property.encode(&mut buffer).unwrap();
+
+        let expected_buffer = [
+            0x26, // user property identifier
+            0x00, 0x01, // name length
+            0x61, // a
+            0x00, 0x02, // value length
+            0x62, 0x63, // bc
+        ];
+
+        assert_eq!(encoded_len, 8);
+        assert_eq!(buffer, expected_buffer);
+    }
+
+    #[test]
+    fn decodes_subscription_identifier() {
+        let buffer = [0x0B, 0x80, 0x01]; // subscription identifier of 128
+
+        let (property, consumed) = Property::decode(&buffer).unwrap();
+
+        assert_eq!(property, Property::SubscriptionIdentifier(128));
+        assert_eq!(consumed, 3);
+    }
+
+    #[test]
+    fn decodes_borrowed_string() {
+        let buffer = [
+            0x03, // content type identifier
+            0x00, 0x04, // length
+            0x6A, 0x73, 0x6F, 0x6E, // json
+        ];
+
+        let (property, consumed) = Property::decode(&buffer).unwrap();
+
+        assert_eq!(property, Property::ContentType("json"));
+        assert_eq!(consumed, 7);
+    }
+
+    #[test]
+    fn rejects_unknown_identifier() {
+        let buffer = [0x04, 0x00];
+
+        assert_eq!(
+            Property::decode(&buffer),
+            Err(MqttError::InvalidPropertyIdentifier)
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_value() {
+        let buffer = [0x21, 0x00]; // receive maximum missing its second byte
+
+        assert_eq!(
+            Property::decode(&buffer),
+            Err(MqttError::MalformedProperties)
+        );
+    }
+
+    #[test]
+    fn rejects_null_in_string() {
+        let property = Property::ReasonString("a\0b");
+        let mut buffer = [0; 8];
+
+        assert_eq!(
+            property.encode(&mut buffer),
+            Err(MqttError::DataRepresentation(
+                DataRepresentationError::NullTerminatorInString
+            ))
+        );
+    }
+
+    #[test]
+    fn encoded_len_matches_encode() {
+        let properties = [
+            Property::PayloadFormatIndicator(1),
+            Property::TopicAlias(10),
+            Property::MaximumPacketSize(1024),
+            Property::SubscriptionIdentifier(268_435_455),
+            Property::ResponseTopic("a/b"),
+            Property::CorrelationData(&[1, 2, 3]),
+            Property::UserProperty("key", "value"),
+        ];
+        let mut buffer = [0; 32];
+
+        for property in properties {
+            assert_eq!(
+                property.encode(&mut buffer).unwrap(),
+                property.encoded_len()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_properties {
+    use super::*;
+
+    #[test]
+    fn encodes_empty_properties() {
+        let properties = Properties::<4>::new();
+        let mut buffer = [0xFF; 1];
+
+        let encoded_len = properties.encode(&mut buffer).unwrap();
+
+        assert_eq!(encoded_len, 1);
+        assert_eq!(buffer, [0x00]);
+    }
+
+    #[test]
+    fn encodes_multiple_properties() {
+        let mut properties = Properties::<4>::new();
+        properties.push(Property::ReceiveMaximum(20)).unwrap();
+        properties.push(Property::TopicAliasMaximum(5)).unwrap();
+
+        let mut buffer = [0; 7];
+
+        let expected_buffer = [
+            0x06, // property length
+            0x21, 0x00, 0x14, // receive maximum of 20
+            0x22, 0x00, 0x05, // topic alias maximum of 5
+        ];
+
+        let encoded_len = properties.encode(&mut buffer).unwrap();
+
+        assert_eq!(encoded_len, 7);
+        assert_eq!(encoded_len, properties.encoded_len());
+        assert_eq!(buffer, expected_buffer);
+    }
+
+    #[test]
+    fn decodes_multiple_properties() {
+        let buffer = [
+            0x08, // property length
+            0x1F, 0x00, 0x02, 0x6F, 0x6B, // reason string "ok"
+            0x23, 0x00, 0x07, // topic alias of 7
+            0xFF, // trailing byte belonging to the next field
+        ];
+
+        let (properties, consumed) = Properties::<4>::decode(&buffer).unwrap();
+        let mut iter = properties.iter();
+
+        assert_eq!(consumed, 9);
+        assert_eq!(properties.len(), 2);
+        assert_eq!(iter.next(), Some(&Property::ReasonString("ok")));
+        assert_eq!(iter.next(), Some(&Property::TopicAlias(7)));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn rejects_property_overrunning_length() {
+        let buffer = [
+            0x02, // property length, too short for the topic alias
+            0x23, 0x00, 0x07, // topic alias of 7
+        ];
+
+        assert_eq!(
+            Properties::<4>::decode(&buffer),
+            Err(MqttError::MalformedProperties)
+        );
+    }
+
+    #[test]
+    fn rejects_too_many_properties() {
+        let mut properties = Properties::<1>::new();
+        properties.push(Property::TopicAlias(1)).unwrap();
+
+        assert_eq!(
+            properties.push(Property::TopicAlias(2)),
+            Err(MqttError::PropertiesCapacityExceeded)
+        );
+    }
+
+    #[test]
+    fn test_reversibility() {
+        let mut original = Properties::<4>::new();
+        original.push(Property::ContentType("text/plain")).unwrap();
+        original.push(Property::UserProperty("k", "v")).unwrap();
+        original
+            .push(Property::SubscriptionIdentifier(300))
+            .unwrap();
+
+        let mut buffer = [0; 32];
+        let encoded_len = original.encode(&mut buffer).unwrap();
+        let (decoded, consumed) = Properties::<4>::decode(&buffer).unwrap();
+
+        assert_eq!(encoded_len, consumed);
+        assert_eq!(original, decoded);
+    }
+}
+
+#[cfg(test)]
+mod test_properties_display {
+    use super::*;
+
+    #[test]
+    fn formats_each_kind_of_value() {
+        assert_eq!(format!("{}", Property::TopicAlias(3)), "TopicAlias=3");
+        assert_eq!(
+            format!("{}", Property::ContentType("text/plain")),
+            "ContentType=\"text/plain\""
+        );
+        assert_eq!(
+            format!("{}", Property::CorrelationData(&[0x01, 0xAB])),
+            "CorrelationData=[01, ab]"
+        );
+        assert_eq!(
+            format!("{}", Property::UserProperty("key", "value")),
+            "UserProperty=\"key\":\"value\""
+        );
+    }
+
+    #[test]
+    fn formats_a_property_block() {
+        let mut properties = Properties::<2>::new();
+        assert_eq!(format!("{properties}"), "{}");
+
+        properties.push(Property::ReasonString("gone")).unwrap();
+        properties.push(Property::ServerKeepAlive(60)).unwrap();
+        assert_eq!(
+            format!("{properties}"),
+            "{ReasonString=\"gone\", ServerKeepAlive=60}"
+        );
+    }
+}